@@ -1,7 +1,14 @@
+use std::any::TypeId;
+use std::time::Duration;
+
 use bevy::{prelude::*, utils::HashMap};
-use bevy_editor_pls_core::{editor_window::EditorWindow, Editor, EditorEvent};
+use bevy_editor_pls_core::{
+    editor_window::{EditorWindow, LinkedState},
+    Editor, EditorEvent,
+};
+use bevy_editor_pls_default_windows::hierarchy::HierarchyState;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Button {
     Keyboard(KeyCode),
     Mouse(MouseButton),
@@ -11,6 +18,44 @@ pub enum Button {
 pub enum UserInput {
     Single(Button),
     Chord(Vec<Button>),
+    /// Each step has to trigger within `timeout` of the previous one, e.g. `G` then `X`.
+    /// Pressing a button that isn't part of the expected step restarts the sequence.
+    Sequence {
+        steps: Vec<UserInput>,
+        timeout: Duration,
+    },
+    /// Triggers once the input has been held down for the given duration
+    Hold(Box<UserInput>, Duration),
+    /// Triggers when the input stops being held down
+    Released(Box<UserInput>),
+}
+
+/// Identifies an [`EditorWindow`] type for use in [`BindingCondition`]s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowKind {
+    type_id: TypeId,
+    name: &'static str,
+}
+
+impl WindowKind {
+    pub fn of<W: EditorWindow>() -> Self {
+        WindowKind {
+            type_id: TypeId::of::<W>(),
+            name: pretty_window_name(std::any::type_name::<W>()),
+        }
+    }
+
+    fn matches(&self, editor: &Editor, window: Option<Entity>) -> bool {
+        window.and_then(|window| editor.window_type_id(window)) == Some(self.type_id)
+    }
+}
+
+fn pretty_window_name(type_name: &'static str) -> &'static str {
+    type_name
+        .rsplit("::")
+        .next()
+        .unwrap_or(type_name)
+        .trim_end_matches("Window")
 }
 
 #[derive(Debug)]
@@ -18,10 +63,25 @@ pub enum BindingCondition {
     InViewport(bool),
     EditorActive(bool),
     ListeningForText(bool),
+    /// The active tab of the focused dock leaf is a window of this type
+    WindowFocused(WindowKind),
+    /// The pointer is over the contents of a window of this type
+    WindowHovered(WindowKind),
+    /// The hierarchy of the focused window (or the default hierarchy) has selected entities
+    HasSelection(bool),
 }
 
 impl BindingCondition {
-    fn evaluate(&self, editor: &Editor) -> bool {
+    pub fn window_focused<W: EditorWindow>() -> Self {
+        BindingCondition::WindowFocused(WindowKind::of::<W>())
+    }
+
+    pub fn window_hovered<W: EditorWindow>() -> Self {
+        BindingCondition::WindowHovered(WindowKind::of::<W>())
+    }
+
+    fn evaluate(&self, cx: &BindingContext) -> bool {
+        let editor = cx.editor;
         match *self {
             BindingCondition::InViewport(in_viewport) => {
                 if in_viewport {
@@ -34,6 +94,9 @@ impl BindingCondition {
             BindingCondition::ListeningForText(listening) => {
                 listening == editor.listening_for_text()
             }
+            BindingCondition::WindowFocused(kind) => kind.matches(editor, editor.focused_window()),
+            BindingCondition::WindowHovered(kind) => kind.matches(editor, editor.hovered_window()),
+            BindingCondition::HasSelection(has_selection) => has_selection == cx.has_selection,
         }
     }
 }
@@ -47,11 +110,25 @@ impl std::fmt::Display for BindingCondition {
             BindingCondition::EditorActive(false) => "editor is not active",
             BindingCondition::ListeningForText(true) => "a ui field is listening for text",
             BindingCondition::ListeningForText(false) => "no ui fields are listening for text",
+            BindingCondition::WindowFocused(kind) => {
+                return write!(f, "{} window is focused", kind.name)
+            }
+            BindingCondition::WindowHovered(kind) => {
+                return write!(f, "mouse over {} window", kind.name)
+            }
+            BindingCondition::HasSelection(true) => "an entity is selected",
+            BindingCondition::HasSelection(false) => "no entity is selected",
         };
         f.write_str(str)
     }
 }
 
+/// Editor state that [`BindingCondition`]s are evaluated against
+pub struct BindingContext<'a> {
+    pub editor: &'a Editor,
+    pub has_selection: bool,
+}
+
 #[derive(Debug)]
 pub struct Binding {
     pub input: UserInput,
@@ -90,11 +167,55 @@ impl Button {
     }
 }
 
+/// Progress of a [`UserInput`] across frames, mirroring the shape of the input
+#[derive(Debug, Default)]
+struct InputState {
+    /// `Sequence`: index of the next step and when the previous step triggered
+    next_step: usize,
+    last_step: Duration,
+    /// `Hold`: when the input started being held, and whether it already triggered since
+    held_since: Option<Duration>,
+    fired: bool,
+    /// `Released`: whether the input was held last frame
+    was_held: bool,
+    children: Vec<InputState>,
+}
+
 impl UserInput {
-    fn just_pressed(
+    /// Whether the input is currently held down. Sequences and releases can't be held.
+    fn held(
         &self,
         keyboard_input: &ButtonInput<KeyCode>,
         mouse_input: &ButtonInput<MouseButton>,
+    ) -> bool {
+        match self {
+            UserInput::Single(single) => single.pressed(keyboard_input, mouse_input),
+            UserInput::Chord(chord) => {
+                !chord.is_empty()
+                    && chord
+                        .iter()
+                        .all(|key| key.pressed(keyboard_input, mouse_input))
+            }
+            UserInput::Hold(input, _) => input.held(keyboard_input, mouse_input),
+            UserInput::Sequence { .. } | UserInput::Released(_) => false,
+        }
+    }
+
+    fn contains(&self, button: Button) -> bool {
+        match self {
+            UserInput::Single(single) => *single == button,
+            UserInput::Chord(chord) => chord.contains(&button),
+            UserInput::Sequence { steps, .. } => steps.iter().any(|step| step.contains(button)),
+            UserInput::Hold(input, _) | UserInput::Released(input) => input.contains(button),
+        }
+    }
+
+    fn triggered(
+        &self,
+        state: &mut InputState,
+        keyboard_input: &ButtonInput<KeyCode>,
+        mouse_input: &ButtonInput<MouseButton>,
+        now: Duration,
     ) -> bool {
         match self {
             UserInput::Single(single) => single.just_pressed(keyboard_input, mouse_input),
@@ -107,34 +228,109 @@ impl UserInput {
                 }
                 [] => false,
             },
+            UserInput::Sequence { steps, timeout } => {
+                if steps.is_empty() {
+                    return false;
+                }
+                state.children.resize_with(steps.len(), default);
+
+                let timed_out = now.saturating_sub(state.last_step) > *timeout;
+                if state.next_step > 0 && timed_out {
+                    state.next_step = 0;
+                }
+
+                let step = &steps[state.next_step];
+                let step_state = &mut state.children[state.next_step];
+                if step.triggered(step_state, keyboard_input, mouse_input, now) {
+                    state.next_step += 1;
+                    state.last_step = now;
+                    if state.next_step == steps.len() {
+                        state.next_step = 0;
+                        return true;
+                    }
+                    return false;
+                }
+
+                let pressed_other = keyboard_input
+                    .get_just_pressed()
+                    .map(|key| Button::Keyboard(*key))
+                    .chain(
+                        mouse_input
+                            .get_just_pressed()
+                            .map(|button| Button::Mouse(*button)),
+                    )
+                    .any(|button| !step.contains(button));
+                if state.next_step > 0 && pressed_other {
+                    // the wrong key may well be the start of a new attempt
+                    state.next_step = 0;
+                    state.children.iter_mut().for_each(|child| *child = default());
+                    // only reachable for sequences of two or more steps
+                    if steps[0].triggered(&mut state.children[0], keyboard_input, mouse_input, now)
+                    {
+                        state.next_step = 1;
+                        state.last_step = now;
+                    }
+                }
+                false
+            }
+            UserInput::Hold(input, duration) => {
+                if !input.held(keyboard_input, mouse_input) {
+                    state.held_since = None;
+                    state.fired = false;
+                    return false;
+                }
+                let held_since = *state.held_since.get_or_insert(now);
+                if !state.fired && now.saturating_sub(held_since) >= *duration {
+                    state.fired = true;
+                    return true;
+                }
+                false
+            }
+            UserInput::Released(input) => {
+                let held = input.held(keyboard_input, mouse_input);
+                let released = state.was_held && !held;
+                state.was_held = held;
+                released
+            }
         }
     }
 }
 
 impl Binding {
-    fn just_pressed(
+    fn triggered(
         &self,
+        state: &mut InputState,
         keyboard_input: &ButtonInput<KeyCode>,
         mouse_input: &ButtonInput<MouseButton>,
-        editor: &Editor,
+        now: Duration,
+        cx: &BindingContext,
     ) -> bool {
         let can_trigger = self
             .conditions
             .iter()
-            .all(|condition| condition.evaluate(editor));
+            .all(|condition| condition.evaluate(cx));
         if !can_trigger {
+            // e.g. don't finish a sequence that was half typed into a text field
+            *state = default();
             return false;
         }
 
-        self.input.just_pressed(keyboard_input, mouse_input)
+        self.input.triggered(state, keyboard_input, mouse_input, now)
     }
 }
 
-#[derive(PartialEq, Eq, Hash)]
+/// Per-binding progress of sequences, holds and releases, owned by [`editor_controls_system`]
+#[derive(Default)]
+pub struct BindingStates(HashMap<(Action, usize), InputState>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Action {
     PlayPauseEditor,
     PauseUnpauseTime,
     FocusSelected,
+    DuplicateSelected,
+    SelectionBack,
+    SelectionForward,
 
     // maybe investigate [GizmoOptions].hotkeys
     // https://docs.rs/transform-gizmo-bevy/latest/transform_gizmo_bevy/struct.GizmoHotkeys.html
@@ -152,6 +348,9 @@ impl std::fmt::Display for Action {
             Action::PlayPauseEditor => write!(f, "Play/Pause editor"),
             Action::PauseUnpauseTime => write!(f, "Pause/Unpause time"),
             Action::FocusSelected => write!(f, "Focus Selected Entity"),
+            Action::DuplicateSelected => write!(f, "Duplicate Selected Entities"),
+            Action::SelectionBack => write!(f, "Previous Selection"),
+            Action::SelectionForward => write!(f, "Next Selection"),
             #[cfg(feature = "default_windows")]
             Action::SetGizmoModeTranslate => write!(f, "Activate translation gizmo"),
             #[cfg(feature = "default_windows")]
@@ -179,14 +378,21 @@ impl EditorControls {
     fn just_pressed(
         &self,
         action: Action,
+        states: &mut BindingStates,
         keyboard_input: &ButtonInput<KeyCode>,
         mouse_input: &ButtonInput<MouseButton>,
-        editor: &Editor,
+        now: Duration,
+        cx: &BindingContext,
     ) -> bool {
-        let bindings = &self.actions.get(&action).unwrap();
+        let bindings = self.get(&action);
+        // every binding has to be evaluated so that their states advance
         bindings
             .iter()
-            .any(|binding| binding.just_pressed(keyboard_input, mouse_input, editor))
+            .enumerate()
+            .fold(false, |triggered, (i, binding)| {
+                let state = states.0.entry((action, i)).or_default();
+                binding.triggered(state, keyboard_input, mouse_input, now, cx) || triggered
+            })
     }
 }
 
@@ -194,16 +400,67 @@ pub fn editor_controls_system(
     controls: Res<EditorControls>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    time: Res<Time<Real>>,
+    mut states: Local<BindingStates>,
     mut editor_events: EventWriter<EditorEvent>,
     mut editor: ResMut<Editor>,
+    hierarchy: LinkedState<HierarchyState>,
 ) {
-    if controls.just_pressed(
+    if controls.is_changed() {
+        // binding indices may have shifted
+        states.0.clear();
+    }
+    let now = time.elapsed();
+
+    let cx = BindingContext {
+        editor: &editor,
+        has_selection: hierarchy
+            .get(editor.focused_window())
+            .is_some_and(|state| !state.selected.is_empty()),
+    };
+
+    let toggle_editor = controls.just_pressed(
         Action::PlayPauseEditor,
+        &mut states,
+        &keyboard_input,
+        &mouse_input,
+        now,
+        &cx,
+    );
+    let focus_selected = controls.just_pressed(
+        Action::FocusSelected,
+        &mut states,
+        &keyboard_input,
+        &mouse_input,
+        now,
+        &cx,
+    );
+    let duplicate_selected = controls.just_pressed(
+        Action::DuplicateSelected,
+        &mut states,
+        &keyboard_input,
+        &mouse_input,
+        now,
+        &cx,
+    );
+    let selection_back = controls.just_pressed(
+        Action::SelectionBack,
+        &mut states,
+        &keyboard_input,
+        &mouse_input,
+        now,
+        &cx,
+    );
+    let selection_forward = controls.just_pressed(
+        Action::SelectionForward,
+        &mut states,
         &keyboard_input,
         &mouse_input,
-        &editor,
-    ) && !editor.always_active()
-    {
+        now,
+        &cx,
+    );
+
+    if toggle_editor && !editor.always_active() {
         let was_active = editor.active;
         editor.set_active(!was_active);
         editor_events.send(EditorEvent::Toggle {
@@ -222,14 +479,18 @@ pub fn editor_controls_system(
     //     }
     // }
 
-    if controls.just_pressed(
-        Action::FocusSelected,
-        &keyboard_input,
-        &mouse_input,
-        &editor,
-    ) {
+    if focus_selected {
         editor_events.send(EditorEvent::FocusSelected);
     }
+    if duplicate_selected {
+        editor_events.send(EditorEvent::DuplicateSelected);
+    }
+    if selection_back {
+        editor_events.send(EditorEvent::SelectionBack);
+    }
+    if selection_forward {
+        editor_events.send(EditorEvent::SelectionForward);
+    }
 
     // #[cfg(feature = "default_windows")]
     // {
@@ -277,6 +538,8 @@ impl EditorControls {
     /// - `C-Enter`: pause time
     /// - `E`: toggle editor
     /// - `F`: focus on selected entity
+    /// - `C-D`: duplicate selected entities
+    /// - `Mouse4/Mouse5` or `A-Left/A-Right`: previous/next selection
     /// - `T/R/S`: show translate/rotate/scale gizmo
     pub fn default_bindings() -> Self {
         let mut controls = EditorControls::default();
@@ -308,6 +571,47 @@ impl EditorControls {
             },
         );
 
+        controls.insert(
+            Action::DuplicateSelected,
+            Binding {
+                input: UserInput::Chord(vec![
+                    Button::Keyboard(KeyCode::ControlLeft),
+                    Button::Keyboard(KeyCode::KeyD),
+                ]),
+                conditions: vec![
+                    BindingCondition::EditorActive(true),
+                    BindingCondition::ListeningForText(false),
+                    BindingCondition::HasSelection(true),
+                ],
+            },
+        );
+
+        for (action, mouse, key) in [
+            (Action::SelectionBack, MouseButton::Back, KeyCode::ArrowLeft),
+            (Action::SelectionForward, MouseButton::Forward, KeyCode::ArrowRight),
+        ] {
+            controls.insert(
+                action,
+                Binding {
+                    input: UserInput::Single(Button::Mouse(mouse)),
+                    conditions: vec![BindingCondition::EditorActive(true)],
+                },
+            );
+            controls.insert(
+                action,
+                Binding {
+                    input: UserInput::Chord(vec![
+                        Button::Keyboard(KeyCode::AltLeft),
+                        Button::Keyboard(key),
+                    ]),
+                    conditions: vec![
+                        BindingCondition::EditorActive(true),
+                        BindingCondition::ListeningForText(false),
+                    ],
+                },
+            );
+        }
+
         #[cfg(feature = "default_windows")]
         {
             controls.insert(
@@ -354,6 +658,23 @@ impl std::fmt::Display for UserInput {
                     write!(f, " + {}", remaining)?;
                 }
             }
+            UserInput::Sequence { steps, .. } => {
+                let mut iter = steps.iter();
+                let first = iter.next();
+                if let Some(first) = first {
+                    write!(f, "{}", first)?;
+                }
+
+                for remaining in iter {
+                    write!(f, ", then {}", remaining)?;
+                }
+            }
+            UserInput::Hold(input, duration) => {
+                write!(f, "hold {} for {:.2}s", input, duration.as_secs_f32())?;
+            }
+            UserInput::Released(input) => {
+                write!(f, "release {}", input)?;
+            }
         }
         Ok(())
     }
@@ -395,6 +716,9 @@ impl EditorWindow for ControlsWindow {
             Action::PlayPauseEditor,
             Action::PauseUnpauseTime,
             Action::FocusSelected,
+            Action::DuplicateSelected,
+            Action::SelectionBack,
+            Action::SelectionForward,
         ] {
             ui.label(egui::RichText::new(action.to_string()).strong());
             let bindings = controls.get(action);
@@ -404,3 +728,183 @@ impl EditorWindow for ControlsWindow {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drives a single binding frame by frame with hand-made input
+    struct Frames {
+        keyboard: ButtonInput<KeyCode>,
+        mouse: ButtonInput<MouseButton>,
+        editor: Editor,
+        state: InputState,
+        now: Duration,
+    }
+
+    impl Frames {
+        fn new() -> Self {
+            Frames {
+                keyboard: default(),
+                mouse: default(),
+                editor: Editor::new(Entity::PLACEHOLDER, false),
+                state: default(),
+                now: Duration::ZERO,
+            }
+        }
+
+        /// Starts the next frame `millis` later, clearing `just_pressed` like bevy does
+        fn next(&mut self, millis: u64) -> &mut Self {
+            self.keyboard.clear();
+            self.mouse.clear();
+            self.now += Duration::from_millis(millis);
+            self
+        }
+
+        fn press(&mut self, key: KeyCode) -> &mut Self {
+            self.keyboard.press(key);
+            self
+        }
+
+        fn release(&mut self, key: KeyCode) -> &mut Self {
+            self.keyboard.release(key);
+            self
+        }
+
+        /// Taps `key`, releasing it again at the start of the next frame
+        fn tap(&mut self, key: KeyCode) -> &mut Self {
+            self.keyboard.release_all();
+            self.press(key)
+        }
+
+        fn triggered(&mut self, binding: &Binding, has_selection: bool) -> bool {
+            let cx = BindingContext {
+                editor: &self.editor,
+                has_selection,
+            };
+            binding.triggered(&mut self.state, &self.keyboard, &self.mouse, self.now, &cx)
+        }
+    }
+
+    fn key(key: KeyCode) -> UserInput {
+        UserInput::Single(Button::Keyboard(key))
+    }
+
+    fn g_then_x() -> Binding {
+        UserInput::Sequence {
+            steps: vec![key(KeyCode::KeyG), key(KeyCode::KeyX)],
+            timeout: Duration::from_millis(500),
+        }
+        .into()
+    }
+
+    #[test]
+    fn sequence_completes() {
+        let binding = g_then_x();
+        let mut frames = Frames::new();
+
+        frames.tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).tap(KeyCode::KeyX);
+        assert!(frames.triggered(&binding, false));
+
+        // starts over once completed
+        frames.next(100).tap(KeyCode::KeyX);
+        assert!(!frames.triggered(&binding, false));
+    }
+
+    #[test]
+    fn sequence_times_out() {
+        let binding = g_then_x();
+        let mut frames = Frames::new();
+
+        frames.tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(600).tap(KeyCode::KeyX);
+        assert!(!frames.triggered(&binding, false));
+    }
+
+    #[test]
+    fn wrong_key_restarts_sequence() {
+        let binding = g_then_x();
+        let mut frames = Frames::new();
+
+        frames.tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).tap(KeyCode::KeyY);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).tap(KeyCode::KeyX);
+        assert!(!frames.triggered(&binding, false));
+
+        // a wrong key that is the first step starts a new attempt right away
+        frames.next(100).tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).tap(KeyCode::KeyX);
+        assert!(frames.triggered(&binding, false));
+    }
+
+    #[test]
+    fn hold_fires_once_until_released() {
+        let binding: Binding =
+            UserInput::Hold(Box::new(key(KeyCode::Space)), Duration::from_millis(500)).into();
+        let mut frames = Frames::new();
+
+        frames.press(KeyCode::Space);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(300);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(300);
+        assert!(frames.triggered(&binding, false));
+        frames.next(300);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(1000);
+        assert!(!frames.triggered(&binding, false));
+
+        frames.next(100).release(KeyCode::Space);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).press(KeyCode::Space);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(600);
+        assert!(frames.triggered(&binding, false));
+    }
+
+    #[test]
+    fn released_fires_on_release() {
+        let binding: Binding = UserInput::Released(Box::new(key(KeyCode::Space))).into();
+        let mut frames = Frames::new();
+
+        frames.press(KeyCode::Space);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).release(KeyCode::Space);
+        assert!(frames.triggered(&binding, false));
+        frames.next(100);
+        assert!(!frames.triggered(&binding, false));
+    }
+
+    #[test]
+    fn state_resets_when_condition_stops_holding() {
+        let binding = Binding {
+            conditions: vec![BindingCondition::HasSelection(true)],
+            ..g_then_x()
+        };
+        let mut frames = Frames::new();
+
+        frames.tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, true));
+        frames.next(100);
+        assert!(!frames.triggered(&binding, false));
+        frames.next(100).tap(KeyCode::KeyX);
+        assert!(!frames.triggered(&binding, true));
+
+        frames.next(100).tap(KeyCode::KeyG);
+        assert!(!frames.triggered(&binding, true));
+        frames.next(100).tap(KeyCode::KeyX);
+        assert!(frames.triggered(&binding, true));
+    }
+}
@@ -4,8 +4,11 @@
 #[cfg(feature = "default_windows")]
 pub mod controls;
 
+use std::path::PathBuf;
+
 use bevy::{
-    prelude::{Entity, Plugin, Update},
+    input::InputSystem,
+    prelude::{Entity, IntoSystemConfigs, Plugin, PreUpdate},
     text::cosmic_text::Command,
     transform::commands,
     window::{MonitorSelection, Window, WindowPosition, WindowRef, WindowResolution},
@@ -29,6 +32,10 @@ pub use bevy_editor_pls_default_windows as default_windows;
 /// Commonly used types and extension traits
 pub mod prelude {
     pub use crate::{AddEditorWindow, EditorPlugin};
+    pub use bevy_editor_pls_core::edit_mode::{in_editor_mode, in_play_mode, SuspendInEditor};
+    pub use bevy_editor_pls_core::input::{editor_wants_keyboard, editor_wants_pointer};
+    #[cfg(feature = "default_windows")]
+    pub use bevy_editor_pls_default_windows::add::{AddEditorItem, AddItem};
     // #[cfg(feature = "default_windows")]
     // pub use bevy_editor_pls_default_windows::scenes::NotInScene;
 }
@@ -61,6 +68,12 @@ pub enum EditorWindowPlacement {
 #[derive(Default)]
 pub struct EditorPlugin {
     pub window: EditorWindowPlacement,
+    /// Hide keyboard and mouse input from the game while the editor uses it, see [`InputIsolationPlugin`]
+    ///
+    /// [`InputIsolationPlugin`]: bevy_editor_pls_core::input::InputIsolationPlugin
+    pub isolate_input: bool,
+    /// Where editor data like bookmarks is saved instead of `.editor_pls` in the project directory
+    pub data_dir: Option<PathBuf>,
 }
 
 impl EditorPlugin {
@@ -83,6 +96,18 @@ impl EditorPlugin {
             ..Default::default()
         })
     }
+
+    /// Stop the game from seeing input while typing into or dragging in editor UI.
+    pub fn with_input_isolation(mut self) -> Self {
+        self.isolate_input = true;
+        self
+    }
+
+    /// Save editor data like bookmarks to `dir` instead of `.editor_pls` in the project directory.
+    pub fn with_data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(dir.into());
+        self
+    }
 }
 
 impl Plugin for EditorPlugin {
@@ -101,10 +126,24 @@ impl Plugin for EditorPlugin {
         };
 
         app.add_plugins(bevy_editor_pls_core::EditorPlugin { window });
+        if self.isolate_input {
+            app.add_plugins(bevy_editor_pls_core::input::InputIsolationPlugin);
+        }
+
+        if let Some(dir) = &self.data_dir {
+            app.insert_resource(bevy_editor_pls_default_windows::EditorDataDir(dir.clone()));
+        }
 
         app.add_editor_window::<crate::controls::ControlsWindow>();
         app.insert_resource(controls::EditorControls::default_bindings())
-            .add_systems(Update, controls::editor_controls_system);
+            .add_systems(
+                PreUpdate,
+                // sees the input before `InputIsolationPlugin` hides it from the game, e.g. the
+                // mouse back and forward buttons stepping through selections
+                controls::editor_controls_system
+                    .after(InputSystem)
+                    .before(bevy_editor_pls_core::input::InputIsolationSet),
+            );
 
         // if !app.is_plugin_added::<bevy_framepace::FramepacePlugin>() {
         //     app.add_plugins(bevy_framepace::FramepacePlugin);
@@ -114,9 +153,12 @@ impl Plugin for EditorPlugin {
         use bevy_editor_pls_default_windows::prelude::*;
         app.add_plugins(HierarchyWindow);
         app.add_plugins(AssetsWindow);
+        app.add_plugins(BookmarksWindow);
         app.add_plugins(InspectorWindow);
+        app.add_plugins(PresetsWindow);
+        app.add_plugins(WatchesWindow);
         app.add_plugins(DebugSettingsWindow);
-        // app.add_plugins(AddWindow);
+        app.add_plugins(AddWindow);
         app.add_plugins(DiagnosticsWindow);
         app.add_plugins(RendererWindow);
         app.add_plugins(CameraWindow::default()); //TODO rework this, either with CameraWindowPlugin or by moving target camera into different component
@@ -141,6 +183,7 @@ pub fn spawn_default_windows(mut commands: Commands, mut tree: ResMut<EditorTabs
     let h = commands.spawn(HierarchyWindow).set_parent(parent).id();
     let r = commands.spawn(ResourcesWindow).set_parent(parent).id();
     let a = commands.spawn(AssetsWindow).set_parent(parent).id();
+    let b = commands.spawn(BookmarksWindow).set_parent(parent).id();
     let i = commands.spawn(InspectorWindow).set_parent(parent).id();
 
     let d1 = commands.spawn(DebugSettingsWindow).set_parent(parent).id();
@@ -157,6 +200,7 @@ pub fn spawn_default_windows(mut commands: Commands, mut tree: ResMut<EditorTabs
     tree.state.push_to_first_leaf(h.into());
     tree.state.push_to_first_leaf(r.into());
     tree.state.push_to_first_leaf(a.into());
+    tree.state.push_to_first_leaf(b.into());
 
     // I am not a fan of egui_dock
     // this is just to focus on the hierarchy
@@ -0,0 +1,192 @@
+use bevy::app::MainScheduleOrder;
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::Editor;
+
+/// Run condition: the editor is active
+pub fn in_editor_mode(editor: Option<Res<Editor>>) -> bool {
+    editor.is_some_and(|editor| editor.active)
+}
+
+/// Run condition: the editor is not active (or not present)
+pub fn in_play_mode(editor: Option<Res<Editor>>) -> bool {
+    !in_editor_mode(editor)
+}
+
+/// Schedules and system sets registered through [`SuspendInEditor`] stop running
+/// while the editor is active and [`EditModeSuspension::enabled`] is set.
+#[derive(Resource)]
+pub struct EditModeSuspension {
+    pub enabled: bool,
+    schedules: Vec<InternedScheduleLabel>,
+    suspended: Vec<Schedule>,
+}
+
+impl Default for EditModeSuspension {
+    fn default() -> Self {
+        EditModeSuspension {
+            enabled: true,
+            schedules: Vec::new(),
+            suspended: Vec::new(),
+        }
+    }
+}
+
+impl EditModeSuspension {
+    pub fn schedules(&self) -> &[InternedScheduleLabel] {
+        &self.schedules
+    }
+
+    /// Whether suspended schedules are currently taken out of the world
+    pub fn is_suspended(&self) -> bool {
+        !self.suspended.is_empty()
+    }
+}
+
+fn not_suspended(editor: Option<Res<Editor>>, suspension: Option<Res<EditModeSuspension>>) -> bool {
+    let enabled = suspension.is_some_and(|suspension| suspension.enabled);
+    !(enabled && in_editor_mode(editor))
+}
+
+/// Extension trait for [`App`] to stop game systems from running while the editor is active
+pub trait SuspendInEditor {
+    /// Adds a run condition to `set` in `schedule`, so it only runs outside of the editor
+    fn suspend_set_in_editor(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        set: impl SystemSet,
+    ) -> &mut Self;
+    /// Takes the whole schedule (e.g. [`FixedUpdate`]) out of the world while the editor is active.
+    /// Can't be used for [`Main`] and the schedules it runs like [`Update`], the editor itself
+    /// runs there and could never be closed again.
+    fn suspend_schedule_in_editor(&mut self, schedule: impl ScheduleLabel) -> &mut Self;
+}
+
+impl SuspendInEditor for App {
+    fn suspend_set_in_editor(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        set: impl SystemSet,
+    ) -> &mut Self {
+        self.configure_sets(schedule, set.run_if(not_suspended))
+    }
+
+    fn suspend_schedule_in_editor(&mut self, schedule: impl ScheduleLabel) -> &mut Self {
+        let label = schedule.intern();
+        if runs_editor(self.world(), label) {
+            error!(
+                "cannot suspend the `{:?}` schedule in the editor, suspend `FixedMain`, `FixedUpdate` or your own schedules instead",
+                label
+            );
+            return self;
+        }
+
+        let mut suspension = self
+            .world_mut()
+            .get_resource_or_insert_with(EditModeSuspension::default);
+        if !suspension.schedules.contains(&label) {
+            suspension.schedules.push(label);
+        }
+        self
+    }
+}
+
+/// Whether the editor runs in `label`: [`Main`] and the schedules it runs, except for the fixed ones
+fn runs_editor(world: &World, label: InternedScheduleLabel) -> bool {
+    label == Main.intern()
+        || world
+            .get_resource::<MainScheduleOrder>()
+            .is_some_and(|order| {
+                order.labels.contains(&label) || order.startup_labels.contains(&label)
+            })
+}
+
+/// Removes the suspended schedules from [`Schedules`] when entering edit mode, and puts them back afterwards.
+/// Missing schedules are skipped by the main and fixed main schedules, so nothing else needs to know.
+pub(crate) fn suspend_schedules(world: &mut World) {
+    let Some(enabled) = world
+        .get_resource::<EditModeSuspension>()
+        .map(|suspension| suspension.enabled)
+    else {
+        return;
+    };
+    let suspend = enabled
+        && world
+            .get_resource::<Editor>()
+            .is_some_and(|editor| editor.active);
+
+    world.resource_scope(|world, mut suspension: Mut<EditModeSuspension>| {
+        if suspend == suspension.is_suspended() {
+            return;
+        }
+
+        let mut schedules = world.resource_mut::<Schedules>();
+        if suspend {
+            for label in suspension.schedules.clone() {
+                if let Some(schedule) = schedules.remove(label) {
+                    suspension.suspended.push(schedule);
+                }
+            }
+        } else {
+            for schedule in suspension.suspended.drain(..) {
+                schedules.insert(schedule);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    struct Gameplay;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(Editor::new(Entity::PLACEHOLDER, false))
+            .init_resource::<EditModeSuspension>()
+            .init_schedule(Gameplay)
+            .add_systems(Last, suspend_schedules);
+        app
+    }
+
+    fn set_editor_active(app: &mut App, active: bool) {
+        app.world_mut().resource_mut::<Editor>().active = active;
+        app.update();
+    }
+
+    #[test]
+    fn schedules_come_back_after_leaving_editor_mode() {
+        let mut app = app();
+        app.suspend_schedule_in_editor(Gameplay);
+
+        set_editor_active(&mut app, true);
+        assert!(!app.world().resource::<Schedules>().contains(Gameplay));
+        assert!(app.world().resource::<EditModeSuspension>().is_suspended());
+
+        set_editor_active(&mut app, false);
+        assert!(app.world().resource::<Schedules>().contains(Gameplay));
+        assert!(!app.world().resource::<EditModeSuspension>().is_suspended());
+    }
+
+    #[test]
+    fn editor_schedules_are_rejected() {
+        let mut app = app();
+        app.suspend_schedule_in_editor(Main)
+            .suspend_schedule_in_editor(PreUpdate)
+            .suspend_schedule_in_editor(Update)
+            .suspend_schedule_in_editor(PostUpdate)
+            .suspend_schedule_in_editor(Last)
+            .suspend_schedule_in_editor(FixedUpdate);
+
+        let suspension = app.world().resource::<EditModeSuspension>();
+        assert_eq!(suspension.schedules(), &[FixedUpdate.intern()]);
+
+        set_editor_active(&mut app, true);
+        let schedules = app.world().resource::<Schedules>();
+        assert!(schedules.contains(Update));
+        assert!(schedules.contains(Last));
+    }
+}
@@ -1,5 +1,7 @@
+use std::any::TypeId;
+
 use bevy::utils::hashbrown::{HashMap, HashSet};
-use bevy::window::WindowMode;
+use bevy::window::{CursorGrabMode, WindowMode};
 use bevy::{prelude::*};
 use bevy_inspector_egui::bevy_egui::{egui, EguiContext};
 use bevy_trait_query::One;
@@ -12,6 +14,9 @@ use crate::editor_window::{EditorWindow, EditorWindowContext, EditorWindowInstan
 pub enum EditorEvent {
     Toggle { now_active: bool },
     FocusSelected,
+    DuplicateSelected,
+    SelectionBack,
+    SelectionForward,
 }
 
 #[derive(Debug)]
@@ -28,8 +33,14 @@ pub struct Editor {
     pub active: bool,
 
     pointer_used: bool,
+    wants_pointer: bool,
     active_editor_interaction: Option<ActiveEditorInteraction>,
     listening_for_text: bool,
+    focused_window: Option<Entity>,
+    hovered_window: Option<Entity>,
+    /// Cursor visibility and grab mode the game set for the editor window, while the editor is
+    /// active and keeps the cursor free
+    saved_cursor: Option<(bool, CursorGrabMode)>,
     window_cache: HashMap<Entity, Box<dyn EditorWindow>>,
     defined_windows: HashMap<String, Box<dyn EditorWindow>>,
 }
@@ -41,8 +52,12 @@ impl Editor {
 
             active: always_active,
             pointer_used: false,
+            wants_pointer: false,
             active_editor_interaction: None,
             listening_for_text: false,
+            focused_window: None,
+            hovered_window: None,
+            saved_cursor: None,
             window_cache: default(),
             defined_windows: default(),
         }
@@ -80,6 +95,17 @@ impl Editor {
         self.listening_for_text
     }
 
+    /// Whether the pointer is over (or being dragged by) editor UI other than a viewport.
+    /// Unlike [`Editor::pointer_used`] this also accounts for the dock panels while the editor is active.
+    pub fn wants_pointer(&self) -> bool {
+        self.wants_pointer
+    }
+
+    /// Whether an editor text field has keyboard focus
+    pub fn wants_keyboard(&self) -> bool {
+        self.listening_for_text
+    }
+
     pub fn viewport_interaction_active(&self) -> bool {
         !self.pointer_used
             || matches!(
@@ -87,6 +113,23 @@ impl Editor {
                 Some(ActiveEditorInteraction::Viewport)
             )
     }
+
+    /// The window entity of the active tab in the focused dock leaf, if the editor is active
+    pub fn focused_window(&self) -> Option<Entity> {
+        self.focused_window
+    }
+
+    /// The window entity whose tab contents are under the pointer, if the editor is active
+    pub fn hovered_window(&self) -> Option<Entity> {
+        self.hovered_window
+    }
+
+    /// The [`EditorWindow::window_type_id`] of a window entity known to the editor
+    pub fn window_type_id(&self, window: Entity) -> Option<TypeId> {
+        self.window_cache
+            .get(&window)
+            .map(|window| window.window_type_id())
+    }
 }
 
 // pub(crate) type UiFn =
@@ -281,9 +324,15 @@ impl Editor {
         if !self.active {
             // self.editor_floating_windows(world, ctx, internal_state);
             self.pointer_used = ctx.wants_pointer_input();
+            self.wants_pointer = ctx.is_using_pointer() || ctx.is_pointer_over_area();
+            self.listening_for_text = ctx.wants_keyboard_input();
+            self.focused_window = None;
+            self.hovered_window = None;
             return;
         }
 
+        self.hovered_window = None;
+
         let mut tree = std::mem::replace(
             &mut internal_state.state,
             egui_dock::DockState::new(Vec::new()),
@@ -306,10 +355,22 @@ impl Editor {
                 },
             );
         internal_state.state = tree;
+        self.focused_window = internal_state
+            .state
+            .find_active_focused()
+            .map(|(_, tab)| tab.entity);
 
         let pointer_pos = ctx.input(|input| input.pointer.interact_pos());
         self.pointer_used = false; //pointer_pos.map_or(false, |pos| !self.is_in_viewport(pos));
 
+        // the dock area covers the whole window, so only windows without a background count as viewport
+        let over_viewport = self
+            .hovered_window
+            .and_then(|window| self.window_cache.get(&window))
+            .is_some_and(|window| !window.clear_background());
+        self.wants_pointer =
+            ctx.is_using_pointer() || (ctx.is_pointer_over_area() && !over_viewport);
+
         // self.editor_floating_windows(world, ctx, internal_state);
 
         self.listening_for_text = ctx.wants_keyboard_input();
@@ -376,6 +437,65 @@ impl Editor {
     }
 }
 
+impl Editor {
+    /// Puts the cursor the game set back into the editor window at the start of the frame, so
+    /// the game sees and changes its own cursor while the editor is active.
+    ///
+    /// Written without change detection, [`Editor::manage_cursor`] frees the cursor again before
+    /// the window is synced.
+    pub(crate) fn restore_game_cursor(editor: Res<Editor>, mut windows: Query<&mut Window>) {
+        let (true, Some((visible, grab_mode))) = (editor.active, editor.saved_cursor) else {
+            return;
+        };
+        let Ok(mut window) = windows.get_mut(editor.on_window) else {
+            return;
+        };
+        let cursor = &mut window.bypass_change_detection().cursor_options;
+        cursor.visible = visible;
+        cursor.grab_mode = grab_mode;
+    }
+
+    /// Frees the cursor of the editor window while the editor is active, and restores it afterwards.
+    ///
+    /// Whatever the game does to the cursor while the editor is active, like a pause menu
+    /// unlocking it, is remembered and applied once the editor is deactivated.
+    pub(crate) fn manage_cursor(mut editor: ResMut<Editor>, mut windows: Query<&mut Window>) {
+        let Ok(mut window) = windows.get_mut(editor.on_window) else {
+            return;
+        };
+        let cursor = &window.cursor_options;
+        // the cursor of the game, see `restore_game_cursor`
+        let game = (cursor.visible, cursor.grab_mode);
+        let free = (true, CursorGrabMode::None);
+
+        match (editor.active, editor.saved_cursor) {
+            (true, saved) => {
+                editor.saved_cursor = Some(game);
+                if game == free {
+                    return;
+                }
+                // the window was already synced with a free cursor, unless the editor was just
+                // activated
+                let cursor = match saved {
+                    Some(_) => &mut window.bypass_change_detection().cursor_options,
+                    None => &mut window.cursor_options,
+                };
+                cursor.visible = true;
+                cursor.grab_mode = CursorGrabMode::None;
+            }
+            (false, Some(_)) => {
+                // the game's cursor is in the window since `restore_game_cursor`, but the window
+                // was last synced with a free cursor
+                editor.saved_cursor = None;
+                if game != free {
+                    window.set_changed();
+                }
+            }
+            (false, None) => {}
+        }
+    }
+}
+
 struct TabViewer<'a> {
     editor: &'a mut Editor,
     internal_state: &'a mut EditorTabs,
@@ -401,6 +521,9 @@ impl egui_dock::TabViewer for TabViewer<'_> {
             error!("{} >:(", cx.entity);
             return;
         }
+        if ui.rect_contains_pointer(ui.max_rect()) {
+            self.editor.hovered_window = Some(tab.entity);
+        }
         self.editor.window_cache[&tab.entity].ui(self.world, cx, ui);
     }
 
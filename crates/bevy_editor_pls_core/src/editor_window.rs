@@ -2,7 +2,7 @@ use bevy::ecs::bundle::DynamicBundle;
 use bevy::ecs::change_detection::MutUntyped;
 use bevy::ecs::component::Component;
 use bevy::ecs::entity::Entity;
-use bevy::ecs::system::Resource;
+use bevy::ecs::system::{Resource, SystemParam};
 use bevy::ecs::world::{Mut, Ref};
 use bevy::prelude::*;
 use bevy::ptr::{Aligned, OwningPtr};
@@ -71,6 +71,11 @@ pub trait EditorWindow: 'static + Send + Sync + dyn_clone::DynClone {
     fn clear_background(&self) -> bool {
         true
     }
+
+    /// [`TypeId`] of the concrete window type, so windows can be told apart behind `dyn EditorWindow`
+    fn window_type_id(&self) -> TypeId {
+        TypeId::of::<Self>()
+    }
 }
 
 // impl Clone for Box<dyn EditorWindow>
@@ -228,12 +233,113 @@ impl EditorWindowContext<'_> {
 //         });
 // }
 
+/// Where the `M` seen by a window lives, resolved like [`EditorWindowContext::get`]
+enum LinkTarget {
+    Entity(Entity),
+    DefaultData,
+}
+
+fn link_target<M>(
+    window: Option<Entity>,
+    has_state: impl Fn(Entity) -> bool,
+    link: impl Fn(Entity) -> Option<Entity>,
+    default: Option<&DefaultLink<M>>,
+) -> Option<LinkTarget> {
+    if let Some(window) = window {
+        if has_state(window) {
+            return Some(LinkTarget::Entity(window));
+        }
+        if let Some(linked) = link(window).filter(|&linked| has_state(linked)) {
+            return Some(LinkTarget::Entity(linked));
+        }
+    }
+    match default? {
+        DefaultLink::Data(_) => Some(LinkTarget::DefaultData),
+        DefaultLink::Link(entity) => Some(LinkTarget::Entity(*entity)),
+    }
+}
+
+/// The `M` seen by `window`: its own, the one its [`Link<M>`] points to, or the [`DefaultLink<M>`],
+/// like [`EditorWindowContext::get_mut`]. Without a window only the [`DefaultLink<M>`] is used.
+///
+/// `None` when the state was removed from the entity a [`DefaultLink::Link`] points to.
+pub fn linked_state_mut<M: Component>(
+    world: &mut World,
+    window: Option<Entity>,
+) -> Option<Mut<'_, M>> {
+    let target = link_target(
+        window,
+        |entity| world.get::<M>(entity).is_some(),
+        |entity| world.get::<Link<M>>(entity).map(|link| link.0),
+        world.get_resource::<DefaultLink<M>>(),
+    )?;
+    match target {
+        LinkTarget::Entity(entity) => world.get_mut::<M>(entity),
+        LinkTarget::DefaultData => world
+            .get_resource_mut::<DefaultLink<M>>()?
+            .filter_map_unchanged(DefaultLink::data_mut),
+    }
+}
+
+/// [`linked_state_mut`] for systems
+#[derive(SystemParam)]
+pub struct LinkedState<'w, 's, M: Component> {
+    states: Query<'w, 's, &'static mut M>,
+    links: Query<'w, 's, &'static Link<M>>,
+    default: Option<ResMut<'w, DefaultLink<M>>>,
+}
+
+impl<M: Component> LinkedState<'_, '_, M> {
+    fn target(&self, window: Option<Entity>) -> Option<LinkTarget> {
+        link_target(
+            window,
+            |entity| self.states.contains(entity),
+            |entity| self.links.get(entity).ok().map(|link| link.0),
+            self.default.as_deref(),
+        )
+    }
+
+    pub fn get(&self, window: Option<Entity>) -> Option<&M> {
+        match self.target(window)? {
+            LinkTarget::Entity(entity) => self.states.get(entity).ok(),
+            LinkTarget::DefaultData => self.default.as_deref()?.data(),
+        }
+    }
+
+    pub fn get_mut(&mut self, window: Option<Entity>) -> Option<Mut<'_, M>> {
+        match self.target(window)? {
+            LinkTarget::Entity(entity) => self.states.get_mut(entity).ok(),
+            LinkTarget::DefaultData => self
+                .default
+                .as_mut()?
+                .reborrow()
+                .filter_map_unchanged(DefaultLink::data_mut),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Resource, Reflect)]
 pub enum DefaultLink<M> {
     Data(M),
     Link(Entity),
 }
 
+impl<M> DefaultLink<M> {
+    pub fn data(&self) -> Option<&M> {
+        match self {
+            DefaultLink::Data(data) => Some(data),
+            DefaultLink::Link(_) => None,
+        }
+    }
+
+    pub fn data_mut(&mut self) -> Option<&mut M> {
+        match self {
+            DefaultLink::Data(data) => Some(data),
+            DefaultLink::Link(_) => None,
+        }
+    }
+}
+
 impl<M: Default> Default for DefaultLink<M> {
     fn default() -> Self {
         DefaultLink::Data(M::default())
@@ -0,0 +1,68 @@
+use bevy::ecs::event::Events;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseMotion, MouseWheel};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::EguiPreUpdateSet;
+
+use crate::Editor;
+
+/// Run condition: an editor text field has keyboard focus.
+///
+/// Use `.run_if(not(editor_wants_keyboard))` on game systems reading the keyboard.
+pub fn editor_wants_keyboard(editor: Option<Res<Editor>>) -> bool {
+    editor.is_some_and(|editor| editor.wants_keyboard())
+}
+
+/// Run condition: the pointer is over, or interacting with, editor UI that isn't a viewport.
+pub fn editor_wants_pointer(editor: Option<Res<Editor>>) -> bool {
+    editor.is_some_and(|editor| editor.wants_pointer())
+}
+
+/// Hides keyboard and mouse input from the game while the editor is using it.
+///
+/// Runs in [`PreUpdate`] after egui has read its input, and clears [`ButtonInput<KeyCode>`],
+/// [`ButtonInput<MouseButton>`] and the corresponding events, so game systems in [`Update`] see nothing.
+/// Keys held down while the editor had focus have to be pressed again to be seen by the game.
+///
+/// Editor systems reading input, like the editor controls, run before [`InputIsolationSet`].
+pub struct InputIsolationPlugin;
+
+/// The systems of [`InputIsolationPlugin`] clearing the input
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputIsolationSet;
+
+impl Plugin for InputIsolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                isolate_keyboard.run_if(editor_wants_keyboard),
+                isolate_pointer.run_if(editor_wants_pointer),
+            )
+                .in_set(InputIsolationSet)
+                .after(InputSystem)
+                .after(EguiPreUpdateSet::ProcessInput),
+        );
+    }
+}
+
+fn isolate_keyboard(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+) {
+    keyboard_input.reset_all();
+    keyboard_events.clear();
+}
+
+fn isolate_pointer(
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut button_events: ResMut<Events<MouseButtonInput>>,
+    mut motion_events: ResMut<Events<MouseMotion>>,
+    mut wheel_events: ResMut<Events<MouseWheel>>,
+) {
+    mouse_input.reset_all();
+    button_events.clear();
+    motion_events.clear();
+    wheel_events.clear();
+}
@@ -2,6 +2,10 @@
 pub mod editor;
 /// Trait definition for new editor windows
 pub mod editor_window;
+/// Run conditions and optional isolation of game input from the editor
+pub mod input;
+/// Stopping game schedules and system sets while the editor is active
+pub mod edit_mode;
 
 use std::marker::PhantomData;
 
@@ -46,6 +50,9 @@ impl AddEditorWindow for App {
 pub enum EditorSet {
     /// In [`CoreSet::PostUpdate`]
     UI,
+    /// In [`PostUpdate`], frees the cursor while the editor is active. Game systems in
+    /// [`PostUpdate`] that change the cursor should run before it.
+    Cursor,
 }
 
 pub struct EditorPlugin {
@@ -73,6 +80,7 @@ impl Plugin for EditorPlugin {
 
         app.insert_resource(Editor::new(window_entity, always_active))
             .init_resource::<EditorTabs>()
+            .init_resource::<edit_mode::EditModeSuspension>()
             .add_event::<EditorEvent>()
             .configure_sets(PostUpdate, EditorSet::UI)
             .add_systems(
@@ -82,7 +90,10 @@ impl Plugin for EditorPlugin {
                     .before(TransformSystem::TransformPropagate)
                     .before(CameraUpdateSystem)
                     .before(EguiPostUpdateSet::ProcessOutput),
-            );
+            )
+            .add_systems(First, Editor::restore_game_cursor)
+            .add_systems(PostUpdate, Editor::manage_cursor.in_set(EditorSet::Cursor))
+            .add_systems(Last, edit_mode::suspend_schedules);
     }
 }
 
@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::sync::Arc;
 
 use bevy::render::camera::ScalingMode;
 use bevy::{
@@ -7,21 +8,43 @@ use bevy::{
     render::view::RenderLayers,
 };
 use bevy_editor_pls_core::editor_window::{EditorWindow, EditorWindowContext};
+use bevy_editor_pls_core::AddEditorWindow;
 use bevy_inspector_egui::egui;
 use indexmap::IndexMap;
 
-use crate::hierarchy::HierarchyWindow;
+use crate::hierarchy::HierarchyState;
+use crate::inspector::{InspectorSelection, InspectorState};
 
+/// Extension trait for [`App`] to add entries to the `Add` menus of the editor
+pub trait AddEditorItem {
+    /// Adds `item` to the `section` submenu, or to the top level for an empty `section`
+    fn register_editor_add_item(&mut self, section: &'static str, item: AddItem) -> &mut Self;
+}
+
+impl AddEditorItem for App {
+    fn register_editor_add_item(&mut self, section: &'static str, item: AddItem) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<AddWindowState>()
+            .add(section, item);
+        self
+    }
+}
+
+/// Something that can be spawned, or inserted into existing entities, from an `Add` menu
+#[derive(Clone)]
 pub struct AddItem {
     name: Cow<'static, str>,
-    add_to_entity: fn(&mut World, Entity),
+    add_to_entity: Arc<dyn Fn(&mut World, Entity) + Send + Sync>,
 }
 
 impl AddItem {
-    pub fn new(name: Cow<'static, str>, add_to_entity: fn(&mut World, Entity)) -> Self {
+    pub fn new(
+        name: Cow<'static, str>,
+        add_to_entity: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) -> Self {
         AddItem {
             name,
-            add_to_entity,
+            add_to_entity: Arc::new(add_to_entity),
         }
     }
 
@@ -52,6 +75,8 @@ impl AddItem {
     }
 }
 
+/// The items of the `Add` menus, by section
+#[derive(Resource)]
 pub struct AddWindowState {
     sections: IndexMap<&'static str, Vec<AddItem>>,
 }
@@ -68,47 +93,63 @@ impl AddWindowState {
     }
 }
 
+#[derive(Debug, Clone, Copy, Component, Default)]
 pub struct AddWindow;
 
 impl EditorWindow for AddWindow {
-    type State = AddWindowState;
-
-    const NAME: &'static str = "Add";
-
-    fn ui(world: &mut World, cx: EditorWindowContext, ui: &mut egui::Ui) {
-        add_ui_button(world, ui, cx);
+    fn ui(&self, world: &mut World, mut cx: EditorWindowContext, ui: &mut egui::Ui) {
+        if let Some(entity) = add_entity_button(ui, world) {
+            if let Some(mut hierarchy) = cx.get_mut::<HierarchyState>(world) {
+                hierarchy.selected.select_replace(entity);
+            }
+            if let Some(mut inspector) = cx.get_mut::<InspectorState>(world) {
+                inspector.selected = InspectorSelection::Entities;
+            }
+        }
     }
+}
 
-    fn menu_ui(world: &mut World, cx: EditorWindowContext, ui: &mut egui::Ui) {
-        add_ui_button(world, ui, cx);
+impl Plugin for AddWindow {
+    fn build(&self, app: &mut App) {
+        app.add_editor_window::<Self>();
+        app.init_resource::<AddWindowState>();
     }
 }
 
-fn add_ui_button(world: &mut World, ui: &mut egui::Ui, mut cx: EditorWindowContext) {
-    let state = cx.state::<AddWindow>().unwrap();
+/// `Add` menu spawning a new entity, returns the spawned entity
+pub fn add_entity_button(ui: &mut egui::Ui, world: &mut World) -> Option<Entity> {
+    let state = world.get_resource::<AddWindowState>()?;
+    let add_item = ui.menu_button("Add", |ui| add_ui(ui, state)).inner??;
 
-    let response = ui.menu_button("Add", |ui| {
-        add_ui(ui, state).map(|add_item| {
-            let entity = world.spawn_empty().id();
-            add_item.add_to_entity(world, entity);
-            entity
-        })
-    });
+    let entity = world.spawn_empty().id();
+    add_item.add_to_entity(world, entity);
+    Some(entity)
+}
 
-    if let Some(Some(entity)) = response.inner {
-        if let Some(hierarchy_state) = cx.state_mut::<HierarchyWindow>() {
-            hierarchy_state.selected.select_replace(entity);
+/// `Add` menu inserting into `entities`
+pub fn add_to_entities_button(ui: &mut egui::Ui, world: &mut World, entities: &[Entity]) {
+    let Some(state) = world.get_resource::<AddWindowState>() else {
+        return;
+    };
+    let add_item = ui
+        .menu_button("Add", |ui| add_ui(ui, state))
+        .inner
+        .flatten();
+
+    if let Some(add_item) = add_item {
+        for &entity in entities {
+            add_item.add_to_entity(world, entity);
         }
     }
 }
 
-pub fn add_ui<'a>(ui: &mut egui::Ui, state: &'a AddWindowState) -> Option<&'a AddItem> {
+pub fn add_ui(ui: &mut egui::Ui, state: &AddWindowState) -> Option<AddItem> {
     for (section_name, items) in &state.sections {
         if section_name.is_empty() {
             for item in items {
                 if ui.button(item.name.as_ref()).clicked() {
                     ui.close_menu();
-                    return Some(item);
+                    return Some(item.clone());
                 }
             }
         } else {
@@ -116,7 +157,7 @@ pub fn add_ui<'a>(ui: &mut egui::Ui, state: &'a AddWindowState) -> Option<&'a Ad
                 for item in items {
                     if ui.button(item.name.as_ref()).clicked() {
                         ui.close_menu();
-                        return Some(item);
+                        return Some(item.clone());
                     }
                 }
                 None
@@ -137,11 +178,6 @@ impl Default for AddWindowState {
 
         state.add("", AddItem::bundle_named::<()>("Empty".into()));
 
-        state.add(
-            "NotInScene",
-            AddItem::component::<crate::scenes::NotInScene>(),
-        );
-
         state.add("Core", AddItem::component::<Name>());
         state.add(
             "Core",
@@ -167,7 +203,7 @@ impl Default for AddWindowState {
         state.add(
             "2D",
             AddItem::new("Orthographic Camera".into(), |world, entity| {
-                world.entity_mut(entity).insert(Camera3d::default());
+                world.entity_mut(entity).insert(Camera2d);
             }),
         );
         state.add("2D", AddItem::bundle::<Sprite>());
@@ -1,6 +1,6 @@
 use bevy::{
     app::Plugin,
-    asset::ReflectAsset,
+    asset::{ReflectAsset, UntypedAssetId},
     ecs::component::Component,
     prelude::{AppTypeRegistry, World},
     reflect::TypeRegistry,
@@ -13,6 +13,10 @@ use bevy_inspector_egui::egui;
 
 use crate::inspector::{InspectorSelection, InspectorState, InspectorWindow};
 
+/// Drag and drop payload of an asset dragged from the assets window
+#[derive(Debug, Clone, Copy)]
+pub struct DraggedAsset(pub UntypedAssetId);
+
 #[derive(Debug, Default, Clone, Copy, Component)]
 pub struct AssetsWindow;
 
@@ -65,10 +69,11 @@ fn select_asset(
                     _ => false,
                 };
 
-                if ui
-                    .selectable_label(selected, format!("{:?}", handle))
-                    .clicked()
-                {
+                // dropped onto handle fields in the inspector
+                let label = ui.dnd_drag_source(egui::Id::new(handle), DraggedAsset(handle), |ui| {
+                    ui.selectable_label(selected, format!("{:?}", handle))
+                });
+                if label.inner.clicked() {
                     *selection =
                         InspectorSelection::Asset(asset_type_id, asset_name.to_owned(), handle);
                 }
@@ -0,0 +1,303 @@
+//! Bookmarked entities, for jumping back to the same few entities during a session.
+//!
+//! Entity ids change between runs, so bookmarks are saved by the [`Name`]s of the entity and
+//! its ancestors, and resolved again when a matching entity shows up.
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy::scene::ron;
+use bevy_editor_pls_core::editor_window::{EditorWindow, EditorWindowContext};
+use bevy_editor_pls_core::AddEditorWindow;
+use bevy_inspector_egui::bevy_inspector::guess_entity_name;
+use bevy_inspector_egui::egui;
+
+use crate::hierarchy::HierarchyState;
+use crate::inspector::{InspectorSelection, InspectorState};
+
+/// Where bookmarks are saved, relative to the [`EditorDataDir`](crate::EditorDataDir)
+const BOOKMARKS_FILE: &str = "bookmarks.ron";
+
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub label: String,
+    /// names of the root ancestor down to the entity, empty for unnamed entities
+    pub path: Vec<String>,
+    /// the entity `path` currently resolves to
+    pub entity: Option<Entity>,
+}
+
+#[derive(Resource, Debug)]
+pub struct Bookmarks {
+    pub entries: Vec<Bookmark>,
+    /// where the bookmarks are saved
+    file: PathBuf,
+}
+
+impl Bookmarks {
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    pub fn load(file: PathBuf) -> Self {
+        let saved = match std::fs::read_to_string(&file) {
+            Ok(text) => ron::from_str(&text).unwrap_or_else(|e| {
+                warn!("Failed to parse bookmarks: {}", e);
+                Vec::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                warn!("Failed to read bookmarks: {}", e);
+                Vec::new()
+            }
+        };
+
+        let entries = saved
+            .into_iter()
+            .map(|(label, path): (String, Vec<String>)| Bookmark {
+                label,
+                path,
+                entity: None,
+            })
+            .collect();
+        Bookmarks { entries, file }
+    }
+
+    pub fn save(&self) {
+        let saved: Vec<(&str, &[String])> = self
+            .entries
+            .iter()
+            .map(|bookmark| (bookmark.label.as_str(), bookmark.path.as_slice()))
+            .collect();
+        let result = ron::ser::to_string_pretty(&saved, default())
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                if let Some(dir) = self.file.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&self.file, text).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            warn!("Failed to save bookmarks: {}", e);
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entries
+            .iter()
+            .any(|bookmark| bookmark.entity == Some(entity))
+    }
+}
+
+/// Names of the root ancestor of `entity` down to `entity`
+pub fn name_path(world: &World, entity: Entity) -> Vec<String> {
+    let mut path: Vec<String> =
+        std::iter::successors(Some(entity), |&e| world.get::<Parent>(e).map(Parent::get))
+            .map(|e| world.get::<Name>(e).map_or("", Name::as_str).to_owned())
+            .collect();
+    path.reverse();
+    path
+}
+
+pub fn is_bookmarked(world: &World, entity: Entity) -> bool {
+    world
+        .get_resource::<Bookmarks>()
+        .is_some_and(|bookmarks| bookmarks.contains(entity))
+}
+
+/// Bookmarks `entity`, or removes its bookmark
+pub fn toggle_bookmark(world: &mut World, entity: Entity) {
+    let label = guess_entity_name(world, entity);
+    let path = name_path(world, entity);
+    let Some(mut bookmarks) = world.get_resource_mut::<Bookmarks>() else {
+        return;
+    };
+
+    match bookmarks
+        .entries
+        .iter()
+        .position(|bookmark| bookmark.entity == Some(entity))
+    {
+        Some(i) => {
+            bookmarks.entries.remove(i);
+        }
+        None => bookmarks.entries.push(Bookmark {
+            label,
+            path,
+            entity: Some(entity),
+        }),
+    }
+    bookmarks.save();
+}
+
+/// Keeps the paths of bookmarked entities up to date and finds entities for unresolved bookmarks.
+/// Only runs when names or the hierarchy changed.
+fn resolve_bookmarks(
+    world: &mut World,
+    mut changed: Local<QueryState<(), Or<(Changed<Name>, Changed<Parent>)>>>,
+    mut named: Local<QueryState<(Entity, &'static Name)>>,
+    mut loaded: Local<bool>,
+) {
+    let despawned = world
+        .resource::<Bookmarks>()
+        .entries
+        .iter()
+        .any(|bookmark| {
+            bookmark
+                .entity
+                .is_some_and(|entity| world.get_entity(entity).is_err())
+        });
+    // `Changed` also matches everything the first time the query runs
+    let changed = changed.iter(world).next().is_some();
+    if !changed && !despawned && *loaded {
+        return;
+    }
+    *loaded = true;
+
+    world.resource_scope(|world, mut bookmarks: Mut<Bookmarks>| {
+        let mut paths_changed = false;
+        let mut unresolved = Vec::new();
+        for bookmark in &mut bookmarks.bypass_change_detection().entries {
+            if let Some(entity) = bookmark.entity {
+                if world.get_entity(entity).is_ok() {
+                    let path = name_path(world, entity);
+                    if path != bookmark.path {
+                        bookmark.path = path;
+                        paths_changed = true;
+                    }
+                    continue;
+                }
+                bookmark.entity = None;
+            }
+
+            // an unnamed entity could be any other unnamed entity next run
+            if bookmark.path.last().is_some_and(|name| !name.is_empty()) {
+                unresolved.push(bookmark);
+            }
+        }
+
+        // one pass over the named entities for all bookmarks, only following the ancestors of
+        // entities with the name a bookmark ends in
+        if !unresolved.is_empty() {
+            for (entity, name) in named.iter(world) {
+                let wanted = |bookmark: &Bookmark| {
+                    bookmark.entity.is_none()
+                        && bookmark
+                            .path
+                            .last()
+                            .is_some_and(|last| last == name.as_str())
+                };
+                if !unresolved.iter().any(|bookmark| wanted(bookmark)) {
+                    continue;
+                }
+                let path = name_path(world, entity);
+                for bookmark in &mut unresolved {
+                    if wanted(bookmark) && bookmark.path == path {
+                        bookmark.entity = Some(entity);
+                    }
+                }
+            }
+        }
+
+        if paths_changed {
+            bookmarks.save();
+        }
+    });
+}
+
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct BookmarksWindow;
+
+impl EditorWindow for BookmarksWindow {
+    fn ui(&self, world: &mut World, mut cx: EditorWindowContext, ui: &mut egui::Ui) {
+        if !world.contains_resource::<Bookmarks>() {
+            return;
+        }
+        let select = world
+            .resource_scope(|_, mut bookmarks: Mut<Bookmarks>| bookmarks_ui(&mut bookmarks, ui));
+
+        if let Some(entity) = select {
+            if let Some(mut hierarchy) = cx.get_mut::<HierarchyState>(world) {
+                hierarchy.selected.select_replace(entity);
+            }
+            if let Some(mut inspector) = cx.get_mut::<InspectorState>(world) {
+                inspector.selected = InspectorSelection::Entities;
+            }
+        }
+    }
+}
+
+/// Lists `bookmarks`, returns the entity of the one that was clicked
+fn bookmarks_ui(bookmarks: &mut Bookmarks, ui: &mut egui::Ui) -> Option<Entity> {
+    let mut changed = false;
+    let mut select = None;
+    let mut remove = None;
+
+    if bookmarks.entries.is_empty() {
+        ui.label("Bookmark entities from their context menu in the hierarchy, or with ☆ in the inspector");
+    }
+
+    for (i, bookmark) in bookmarks.entries.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let id = ui.id().with(("bookmark_rename", i));
+            let renaming = ui.data(|d| d.get_temp::<bool>(id)).unwrap_or(false);
+            if renaming {
+                let response = ui.text_edit_singleline(&mut bookmark.label);
+                response.request_focus();
+                if response.lost_focus() {
+                    ui.data_mut(|d| d.remove::<bool>(id));
+                    changed = true;
+                }
+                return;
+            }
+
+            let text = match bookmark.entity {
+                Some(_) => egui::RichText::new(&bookmark.label),
+                None => egui::RichText::new(&bookmark.label).weak(),
+            };
+            let hover = match bookmark.entity {
+                Some(entity) => format!("{}\n{entity}", bookmark.path.join("/")),
+                None => format!("{}\nnot found", bookmark.path.join("/")),
+            };
+            let response = ui
+                .add_enabled(
+                    bookmark.entity.is_some(),
+                    egui::Button::new(text).frame(false),
+                )
+                .on_hover_text(hover);
+            if response.clicked() {
+                select = bookmark.entity;
+            }
+            if response.double_clicked() {
+                ui.data_mut(|d| d.insert_temp(id, true));
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                    remove = Some(i);
+                }
+                if ui.small_button("✏").on_hover_text("Rename").clicked() {
+                    ui.data_mut(|d| d.insert_temp(id, true));
+                }
+            });
+        });
+    }
+
+    if let Some(i) = remove {
+        bookmarks.entries.remove(i);
+        changed = true;
+    }
+    if changed {
+        bookmarks.save();
+    }
+    select
+}
+
+impl Plugin for BookmarksWindow {
+    fn build(&self, app: &mut App) {
+        app.add_editor_window::<Self>();
+        let file = crate::editor_data_dir(app.world_mut()).join(BOOKMARKS_FILE);
+        app.insert_resource(Bookmarks::load(file))
+            .add_systems(PostUpdate, resolve_bookmarks);
+    }
+}
@@ -0,0 +1,243 @@
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy::render::view::RenderLayers;
+use bevy_editor_pls_core::editor_window::EditorWindowContext;
+use bevy_editor_pls_core::Editor;
+use bevy_inspector_egui::bevy_egui::EguiContextSettings;
+use bevy_inspector_egui::egui;
+
+use super::camera_2d_panzoom::PanCamControls;
+use super::{is_editor_only, EditorCamera};
+use crate::entity_field::EntityPicker;
+use crate::hierarchy::picking::{self, NoEditorPicking};
+use crate::hierarchy::{is_unpickable, EditorLocked, HideInEditor, HierarchyState};
+use crate::inspector::{InspectorSelection, InspectorState};
+
+/// distance in egui points the pointer has to move before a press turns into a marquee
+const DRAG_THRESHOLD: f32 = 4.0;
+
+#[derive(Clone, Copy)]
+enum MarqueeMode {
+    Replace,
+    Add,
+    Subtract,
+}
+
+/// Box and click selection in the viewport of an editor camera.
+///
+/// Shift adds to the selection, Ctrl removes from it. Entities are selected when their
+/// whole [`Aabb`] ends up inside the rectangle, unless an ancestor is hidden, unpickable or locked.
+/// A click without dragging selects the entity under the pointer, or picks it for the
+/// [`EntityPicker`].
+pub(crate) fn marquee_ui(
+    world: &mut World,
+    cx: &EditorWindowContext,
+    camera_entity: Entity,
+    ui: &mut egui::Ui,
+) {
+    let viewport = ui.available_rect_before_wrap();
+    let id = ui.id().with("marquee");
+
+    let pan_uses_primary = world
+        .get::<PanCamControls>(camera_entity)
+        .is_some_and(|c| c.enabled && c.grab_buttons.contains(&MouseButton::Left));
+
+    let (pressed, down, press_origin, pointer, modifiers) = ui.input(|i| {
+        (
+            i.pointer.primary_pressed(),
+            i.pointer.primary_down(),
+            i.pointer.press_origin(),
+            i.pointer.latest_pos(),
+            i.modifiers,
+        )
+    });
+
+    if pressed {
+        let starts_here = press_origin.is_some_and(|origin| {
+            viewport.contains(origin)
+                && ui.ctx().layer_id_at(origin) == Some(ui.layer_id())
+                && !ui.ctx().is_using_pointer()
+        });
+        if starts_here {
+            ui.data_mut(|d| d.insert_temp(id, press_origin.unwrap()));
+        }
+    }
+
+    let Some(start) = ui.data(|d| d.get_temp::<egui::Pos2>(id)) else {
+        return;
+    };
+    let Some(pointer) = pointer else {
+        return;
+    };
+
+    let rect = egui::Rect::from_two_pos(start, pointer).intersect(viewport);
+    let dragging = start.distance(pointer) > DRAG_THRESHOLD;
+
+    if down {
+        if dragging && !pan_uses_primary {
+            let color = ui.visuals().selection.bg_fill;
+            ui.painter().rect(
+                rect,
+                0.0,
+                color.linear_multiply(0.2),
+                egui::Stroke::new(1.0, color),
+                egui::StrokeKind::Inside,
+            );
+        }
+        return;
+    }
+
+    ui.data_mut(|d| d.remove::<egui::Pos2>(id));
+    if dragging && pan_uses_primary {
+        return;
+    }
+
+    let mode = if modifiers.shift {
+        MarqueeMode::Add
+    } else if modifiers.command || modifiers.ctrl {
+        MarqueeMode::Subtract
+    } else {
+        MarqueeMode::Replace
+    };
+
+    if !dragging {
+        let scale = egui_scale(world);
+        let position = Vec2::new(pointer.x * scale, pointer.y * scale);
+        let Some(entity) = picking::entity_at(world, camera_entity, position) else {
+            return;
+        };
+        if let Some(mut picker) = world
+            .get_resource_mut::<EntityPicker>()
+            .filter(|picker| picker.is_picking())
+        {
+            debug!("Picked entity {} for an entity field", entity);
+            picker.pick(entity);
+            return;
+        }
+        debug!("Selecting picked entity {}", entity);
+        select(world, cx, mode, vec![entity]);
+        return;
+    }
+
+    let entities: Vec<Entity> = entities_in_rect(world, camera_entity, rect)
+        .into_iter()
+        .filter(|&entity| !is_unpickable(world, entity))
+        .collect();
+    debug!("Marquee selected {} entities", entities.len());
+    select(world, cx, mode, entities);
+}
+
+/// Changes the selection of the hierarchy linked to the viewport window
+fn select(world: &mut World, cx: &EditorWindowContext, mode: MarqueeMode, entities: Vec<Entity>) {
+    let Some(mut hierarchy) = cx.get_mut::<HierarchyState>(world) else {
+        return;
+    };
+    let selected = &mut hierarchy.selected;
+    match mode {
+        MarqueeMode::Replace => {
+            selected.clear();
+            for entity in entities {
+                selected.select_maybe_add(entity, true);
+            }
+        }
+        MarqueeMode::Add => {
+            for entity in entities {
+                if !selected.contains(entity) {
+                    selected.select_maybe_add(entity, true);
+                }
+            }
+        }
+        MarqueeMode::Subtract => {
+            for entity in entities {
+                selected.remove(entity);
+            }
+        }
+    }
+
+    if let Some(mut inspector) = cx.get_mut::<InspectorState>(world) {
+        inspector.selected = InspectorSelection::Entities;
+    }
+}
+
+/// Window logical pixels per egui point in the editor window
+fn egui_scale(world: &World) -> f32 {
+    let window = world.resource::<Editor>().window();
+    world
+        .get::<EguiContextSettings>(window)
+        .map_or(1.0, |settings| settings.scale_factor)
+}
+
+/// `rect` is in egui points, the same space as the editor ui
+fn entities_in_rect(world: &mut World, camera_entity: Entity, rect: egui::Rect) -> Vec<Entity> {
+    let egui_scale = egui_scale(world);
+    let mut state: SystemState<(
+        Query<(&Camera, &GlobalTransform, Option<&RenderLayers>)>,
+        Query<
+            (
+                Entity,
+                &GlobalTransform,
+                &Aabb,
+                Option<&InheritedVisibility>,
+                Option<&RenderLayers>,
+            ),
+            (
+                Without<HideInEditor>,
+                Without<NoEditorPicking>,
+                Without<EditorLocked>,
+                Without<EditorCamera>,
+            ),
+        >,
+    )> = SystemState::new(world);
+    let (cameras, candidates) = state.get(world);
+
+    let Ok((camera, camera_transform, camera_layers)) = cameras.get(camera_entity) else {
+        return Vec::new();
+    };
+    let Some(viewport) = camera.logical_viewport_rect() else {
+        return Vec::new();
+    };
+    // window logical pixels, the space `Camera::logical_viewport_rect` is in
+    let selection = Rect::new(
+        rect.min.x * egui_scale,
+        rect.min.y * egui_scale,
+        rect.max.x * egui_scale,
+        rect.max.y * egui_scale,
+    );
+
+    let camera_layers = camera_layers.cloned().unwrap_or_default();
+
+    candidates
+        .iter()
+        .filter(|(_, _, _, visibility, layers)| {
+            visibility.map_or(true, |v| v.get())
+                && !is_editor_only(*layers)
+                && layers
+                    .cloned()
+                    .unwrap_or_default()
+                    .intersects(&camera_layers)
+        })
+        .filter(|(_, transform, aabb, _, _)| {
+            aabb_corners(aabb).into_iter().all(|corner| {
+                camera
+                    .world_to_viewport(camera_transform, transform.transform_point(corner))
+                    .is_ok_and(|p| selection.contains(viewport.min + p))
+            })
+        })
+        .map(|(entity, ..)| entity)
+        .collect()
+}
+
+fn aabb_corners(aabb: &Aabb) -> [Vec3; 8] {
+    let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+    [
+        Vec3::new(min.x, min.y, min.z),
+        Vec3::new(max.x, min.y, min.z),
+        Vec3::new(min.x, max.y, min.z),
+        Vec3::new(max.x, max.y, min.z),
+        Vec3::new(min.x, min.y, max.z),
+        Vec3::new(max.x, min.y, max.z),
+        Vec3::new(min.x, max.y, max.z),
+        Vec3::new(max.x, max.y, max.z),
+    ]
+}
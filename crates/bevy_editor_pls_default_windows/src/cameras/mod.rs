@@ -1,6 +1,7 @@
 pub mod camera_2d_panzoom;
 pub mod camera_3d_free;
 pub mod camera_3d_panorbit;
+mod marquee;
 // use crate::scenes::NotInScene;
 
 use std::any::type_name;
@@ -25,12 +26,17 @@ use bevy_inspector_egui::egui;
 use camera_2d_panzoom::PanCamControls;
 use camera_3d_free::FlycamControls;
 use transform_gizmo_bevy::GizmoCamera;
-// use bevy_mod_picking::prelude::PickRaycastSource;
 
 use self::camera_3d_panorbit::PanOrbitCamera;
+use crate::hierarchy::highlight;
 
 pub const EDITOR_RENDER_LAYER: usize = 19;
 
+/// Entities only visible to editor cameras, like gizmo markers
+pub fn is_editor_only(layers: Option<&RenderLayers>) -> bool {
+    layers.is_some_and(|layers| *layers == RenderLayers::layer(EDITOR_RENDER_LAYER))
+}
+
 // Present on all editor cameras
 #[derive(Component)]
 pub struct EditorCamera;
@@ -106,6 +112,8 @@ impl EditorWindow for CameraWindow {
         };
 
         if !is_editor_cam {
+            state.apply(world);
+            highlight::ui_node_outlines(world, camera_entity, ui);
             return;
         }
 
@@ -188,6 +196,9 @@ impl EditorWindow for CameraWindow {
         });
 
         state.apply(world);
+
+        marquee::marquee_ui(world, &cx, camera_entity, ui);
+        highlight::ui_node_outlines(world, camera_entity, ui);
     }
 
     fn menu_ui(&self, world: &mut World, mut _cx: EditorWindowContext, ui: &mut egui::Ui) {
@@ -405,12 +416,15 @@ fn toggle_editor_cam(
     primary_window: Single<Entity, With<PrimaryWindow>>,
     mut commands: Commands,
 ) {
-    if editor_events.is_empty() {
+    let toggled = editor_events
+        .read()
+        .filter(|event| matches!(event, EditorEvent::Toggle { .. }))
+        .count()
+        > 0;
+    if !toggled {
         return;
     }
 
-    editor_events.clear();
-
     if editor.active {
         for (entity, camera, saved, is_editor_cam) in cam_query.iter_mut() {
             if target_window(&camera, *primary_window) == Some(editor.window())
@@ -0,0 +1,270 @@
+//! Adding any registered component to entities through reflection, copying component values
+//! between entities and removing components.
+
+use std::any::TypeId;
+
+use bevy::ecs::component::ComponentId;
+use bevy::ecs::reflect::ReflectFromWorld;
+use bevy::prelude::*;
+use bevy::reflect::serde::ReflectSerializer;
+use bevy::reflect::std_traits::ReflectDefault;
+use bevy::reflect::TypeRegistration;
+use bevy::scene::ron;
+use bevy_inspector_egui::egui;
+
+const NOT_CONSTRUCTIBLE: &str = "Needs `ReflectDefault` or `ReflectFromWorld` to be added";
+
+struct ComponentEntry {
+    name: &'static str,
+    type_id: TypeId,
+    /// has [`ReflectDefault`] or [`ReflectFromWorld`]
+    constructible: bool,
+}
+
+/// Searchable menu of every registered component, inserted into all of `entities`.
+///
+/// Components that can't be constructed through reflection are listed, but disabled.
+pub fn add_component_button(ui: &mut egui::Ui, world: &mut World, entities: &[Entity]) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let search_id = ui.id().with("add_component_search");
+    let mut insert = None;
+    ui.menu_button("Add Component", |ui| {
+        let mut search = ui
+            .data(|d| d.get_temp::<String>(search_id))
+            .unwrap_or_default();
+        ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search"));
+        let lowercase = search.to_lowercase();
+
+        let mut components: Vec<ComponentEntry> = type_registry
+            .iter()
+            .filter(|registration| registration.data::<ReflectComponent>().is_some())
+            .filter(|registration| !on_all_entities(world, entities, registration.type_id()))
+            .map(|registration| ComponentEntry {
+                name: registration.type_info().type_path_table().short_path(),
+                type_id: registration.type_id(),
+                constructible: registration.data::<ReflectDefault>().is_some()
+                    || registration.data::<ReflectFromWorld>().is_some(),
+            })
+            .filter(|entry| entry.name.to_lowercase().contains(&lowercase))
+            .collect();
+        components.sort_by_key(|entry| entry.name);
+
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for entry in components {
+                    let response = match entry.constructible {
+                        true => ui.button(entry.name),
+                        false => {
+                            ui.add_enabled(false, egui::Button::new(format!("⚠ {}", entry.name)))
+                        }
+                    };
+                    // registers the component, so only for the hovered one
+                    let required = match response.contains_pointer() {
+                        true => required_components(world, &type_registry, entry.type_id),
+                        false => Vec::new(),
+                    };
+                    let required = match required.is_empty() {
+                        true => None,
+                        false => Some(required.join(", ")),
+                    };
+                    let response = match (entry.constructible, required) {
+                        (false, required) => response.on_disabled_hover_text(format!(
+                            "{NOT_CONSTRUCTIBLE}\nRequires {}",
+                            required.as_deref().unwrap_or("nothing")
+                        )),
+                        (true, Some(required)) => {
+                            response.on_hover_text(format!("Also inserts {required}"))
+                        }
+                        (true, None) => response,
+                    };
+                    if response.clicked() {
+                        insert = Some(entry.type_id);
+                        ui.close_menu();
+                    }
+                }
+            });
+
+        ui.data_mut(|d| d.insert_temp(search_id, search));
+    });
+
+    let Some(type_id) = insert else {
+        return;
+    };
+    let Some(registration) = type_registry.get(type_id) else {
+        return;
+    };
+    for &entity in entities {
+        insert_default(world, registration, entity, &type_registry);
+    }
+}
+
+fn on_all_entities(world: &World, entities: &[Entity], type_id: TypeId) -> bool {
+    let Some(component_id) = world.components().get_id(type_id) else {
+        return false;
+    };
+    entities.iter().all(|&entity| {
+        world
+            .get_entity(entity)
+            .is_ok_and(|entity| entity.contains_id(component_id))
+    })
+}
+
+/// Components inserted along with `type_id` through `#[require]`
+fn required_components(
+    world: &mut World,
+    type_registry: &bevy::reflect::TypeRegistry,
+    type_id: TypeId,
+) -> Vec<String> {
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) else {
+        return Vec::new();
+    };
+    let component_id = reflect_component.register_component(world);
+    let components = world.components();
+    let Some(info) = components.get_info(component_id) else {
+        return Vec::new();
+    };
+    let mut required: Vec<String> = info
+        .required_components()
+        .iter_ids()
+        .filter_map(|id| components.get_info(id))
+        .map(|info| pretty_type_name::pretty_type_name_str(info.name()))
+        .collect();
+    required.sort();
+    required
+}
+
+/// Inserts the default value of the component registered as `registration`
+pub fn insert_default(
+    world: &mut World,
+    registration: &TypeRegistration,
+    entity: Entity,
+    type_registry: &bevy::reflect::TypeRegistry,
+) {
+    let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+        return;
+    };
+    let value = match (
+        registration.data::<ReflectDefault>(),
+        registration.data::<ReflectFromWorld>(),
+    ) {
+        (Some(default), _) => default.default(),
+        (None, Some(from_world)) => from_world.from_world(world),
+        (None, None) => return,
+    };
+    if let Ok(mut entity) = world.get_entity_mut(entity) {
+        reflect_component.insert(&mut entity, value.as_partial_reflect(), type_registry);
+    }
+}
+
+/// Components all of `entities` have, in the order of the first entity's archetype
+pub fn shared_components(world: &World, entities: &[Entity]) -> Vec<ComponentId> {
+    let Some(Ok(first)) = entities.first().map(|&entity| world.get_entity(entity)) else {
+        return Vec::new();
+    };
+    first
+        .archetype()
+        .components()
+        .filter(|&id| {
+            entities.iter().all(|&entity| {
+                world
+                    .get_entity(entity)
+                    .is_ok_and(|entity| entity.contains_id(id))
+            })
+        })
+        .collect()
+}
+
+/// Removes a component by id, keeping the hierarchy consistent for [`Parent`] and [`Children`]
+pub fn remove_component(world: &mut World, entity: Entity, component_id: ComponentId) {
+    let Ok(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if Some(component_id) == entity.world().component_id::<Parent>() {
+        entity.remove_parent();
+    } else if Some(component_id) == entity.world().component_id::<Children>() {
+        entity.clear_children();
+    } else {
+        entity.remove_by_id(component_id);
+    }
+}
+
+/// The component value copied from the inspector
+#[derive(Resource, Default)]
+pub struct ComponentClipboard {
+    pub copied: Option<CopiedComponent>,
+}
+
+pub struct CopiedComponent {
+    pub type_id: TypeId,
+    pub name: String,
+    pub value: Box<dyn PartialReflect>,
+}
+
+/// Copies the component into the [`ComponentClipboard`], returning it as RON for the system clipboard
+pub fn copy_component(
+    world: &mut World,
+    entity: Entity,
+    component_id: ComponentId,
+) -> Option<String> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let info = world.components().get_info(component_id)?;
+    let type_id = info.type_id()?;
+    let name = pretty_type_name::pretty_type_name_str(info.name());
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) else {
+        warn!("Can't copy {name}, it doesn't reflect `Component`");
+        return None;
+    };
+    let value = reflect_component
+        .reflect(world.get_entity(entity).ok()?)?
+        .clone_value();
+
+    let ron = ron::ser::to_string_pretty(
+        &ReflectSerializer::new(value.as_ref(), &type_registry),
+        default(),
+    );
+    let ron = match ron {
+        Ok(ron) => Some(ron),
+        Err(e) => {
+            warn!("Failed to serialize {name}: {e}");
+            None
+        }
+    };
+
+    world.resource_mut::<ComponentClipboard>().copied = Some(CopiedComponent {
+        type_id,
+        name,
+        value,
+    });
+    ron
+}
+
+/// Applies the copied component values to each of `entities`, inserting it where it's missing
+pub fn paste_component(world: &mut World, entities: &[Entity]) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    world.resource_scope(|world, clipboard: Mut<ComponentClipboard>| {
+        let Some(copied) = &clipboard.copied else {
+            return;
+        };
+        let Some(reflect_component) =
+            type_registry.get_type_data::<ReflectComponent>(copied.type_id)
+        else {
+            return;
+        };
+        for &entity in entities {
+            if let Ok(mut entity) = world.get_entity_mut(entity) {
+                reflect_component.apply_or_insert(
+                    &mut entity,
+                    copied.value.as_ref(),
+                    &type_registry,
+                );
+            }
+        }
+    });
+}
@@ -8,7 +8,11 @@ use bevy::{
     reflect::TypeRegistry,
     render::{render_resource::WgpuFeatures, renderer::RenderAdapter},
 };
-use bevy_editor_pls_core::{editor_window::{EditorWindow, EditorWindowContext}, AddEditorWindow};
+use bevy_editor_pls_core::{
+    edit_mode::EditModeSuspension,
+    editor_window::{EditorWindow, EditorWindowContext},
+    AddEditorWindow,
+};
 use bevy_inspector_egui::{
     egui::{self, Grid},
     reflect_inspector::ui_for_value,
@@ -19,10 +23,22 @@ pub struct DebugSettings {
     pub pause_time: bool,
     pub wireframes: bool,
     pub highlight_selected: bool,
+    pub highlight_mode: HighlightMode,
+    /// color of the [`HighlightMode::Outline`] highlight
+    pub highlight_color: Color,
 
     open_debugdump_status: Option<Arc<DebugdumpError>>,
 }
 
+/// How selected entities are highlighted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightMode {
+    /// renders selected meshes as wireframe, requires the `POLYGON_MODE_LINE` GPU feature
+    Wireframe,
+    /// draws the bounds of selected meshes, sprites and UI nodes with gizmos
+    Outline,
+}
+
 #[derive(Debug)]
 enum DebugdumpError {
     DotNotFound,
@@ -37,6 +53,8 @@ impl Default for DebugSettings {
             pause_time: false,
             wireframes: false,
             highlight_selected: true,
+            highlight_mode: HighlightMode::Wireframe,
+            highlight_color: Color::srgb(1.0, 0.6, 0.0),
 
             open_debugdump_status: None,
         }
@@ -70,10 +88,31 @@ impl Plugin for DebugSettingsWindow {
         // app.init_resource::<PreviouslyActiveCameras>();
         app.add_editor_window::<DebugSettingsWindow>();
         app.init_resource::<DebugSettings>();
+        app.add_systems(Startup, fall_back_to_outline_highlight);
         debugdump::setup(app);
     }
 }
 
+/// Whether the GPU can draw wireframes
+fn supports_wireframe(adapter: Option<&RenderAdapter>) -> bool {
+    adapter.map_or(false, |adapter| {
+        adapter
+            .0
+            .features()
+            .contains(WgpuFeatures::POLYGON_MODE_LINE)
+    })
+}
+
+/// Highlights with [`HighlightMode::Outline`] when the GPU can't draw wireframes
+fn fall_back_to_outline_highlight(
+    adapter: Option<Res<RenderAdapter>>,
+    mut settings: ResMut<DebugSettings>,
+) {
+    if !supports_wireframe(adapter.as_deref()) {
+        settings.highlight_mode = HighlightMode::Outline;
+    }
+}
+
 fn debug_ui(
     world: &mut World,
     state: &mut DebugSettings,
@@ -136,14 +175,14 @@ fn debug_ui_options(
         }
         ui.end_row();
 
-        let wireframe_enabled = world
-            .get_resource::<RenderAdapter>()
-            .map_or(false, |adapter| {
-                adapter
-                    .0
-                    .features()
-                    .contains(WgpuFeatures::POLYGON_MODE_LINE)
-            });
+        if let Some(mut suspension) = world.get_resource_mut::<EditModeSuspension>() {
+            ui.label("Suspend game while editing")
+                .on_hover_text("Stops schedules and system sets registered with `SuspendInEditor`");
+            ui.checkbox(&mut suspension.enabled, "");
+            ui.end_row();
+        }
+
+        let wireframe_enabled = supports_wireframe(world.get_resource::<RenderAdapter>());
 
         if wireframe_enabled {
             ui.label("Wireframes");
@@ -159,15 +198,25 @@ fn debug_ui_options(
         });
         ui.end_row();
 
-        if !wireframe_enabled {
-            state.highlight_selected = false;
-        }
-
         ui.label("Highlight selected entity");
-        ui.add_enabled_ui(wireframe_enabled, |ui| {
+        ui.horizontal(|ui| {
             ui.checkbox(&mut state.highlight_selected, "");
+            ui.add_enabled_ui(wireframe_enabled, |ui| {
+                ui.radio_value(
+                    &mut state.highlight_mode,
+                    HighlightMode::Wireframe,
+                    "Wireframe",
+                );
+            });
+            ui.radio_value(&mut state.highlight_mode, HighlightMode::Outline, "Outline");
         });
         ui.end_row();
+
+        if state.highlight_mode == HighlightMode::Outline {
+            ui.label("Highlight color");
+            ui_for_value(&mut state.highlight_color, ui, type_registry);
+            ui.end_row();
+        }
     });
 }
 
@@ -0,0 +1,308 @@
+//! `Entity` fields in the inspector, like in `Link<M>`.
+//!
+//! They show the name of the referenced entity, which selects and reveals it in the hierarchy when
+//! clicked. A field can be set by dropping hierarchy rows onto it, or with its eyedropper and a
+//! click on a hierarchy row or into a viewport.
+//!
+//! [`Parent`] and [`Children`] only show these links, setting them directly would leave the other
+//! side of the hierarchy behind. Reparent by dragging hierarchy rows instead.
+//!
+//! The ui is registered in the [`AppTypeRegistry`], so it also applies to other inspectors of the
+//! game using `bevy_inspector_egui`.
+
+use std::any::{Any, TypeId};
+
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::inspector_egui_impls::InspectorEguiImpl;
+use bevy_inspector_egui::reflect_inspector::{InspectorUi, ProjectorReflect};
+use bevy_inspector_egui::restricted_world_view::RestrictedWorldView;
+
+use crate::hierarchy::{self, DraggedEntities};
+
+/// The `Entity` field waiting for an entity to be picked by its eyedropper
+#[derive(Resource, Default, Debug)]
+pub struct EntityPicker {
+    target: Option<egui::Id>,
+    /// picked for the field, set the next time it is drawn
+    picked: Option<(egui::Id, Entity)>,
+}
+
+impl EntityPicker {
+    pub fn is_picking(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Sets the field waiting for a pick to `entity`
+    pub fn pick(&mut self, entity: Entity) {
+        if let Some(id) = self.target.take() {
+            self.picked = Some((id, entity));
+        }
+    }
+}
+
+/// Replaces the inspector ui of [`Entity`], and shows [`Parent`] and [`Children`] as read-only
+/// links. Runs after the default ui is registered.
+pub(crate) fn register_entity_ui(world: &mut World) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let mut type_registry = type_registry.write();
+    type_registry.register::<Entity>();
+    type_registry.register::<Parent>();
+    type_registry.register::<Children>();
+    if let Some(registration) = type_registry.get_mut(TypeId::of::<Entity>()) {
+        registration.insert(InspectorEguiImpl::new(
+            entity_ui,
+            entity_ui_readonly,
+            entity_ui_many,
+        ));
+    }
+    if let Some(registration) = type_registry.get_mut(TypeId::of::<Parent>()) {
+        registration.insert(InspectorEguiImpl::new(
+            hierarchy_links_ui::<Parent>,
+            hierarchy_links_ui_readonly::<Parent>,
+            hierarchy_links_ui_many::<Parent>,
+        ));
+    }
+    if let Some(registration) = type_registry.get_mut(TypeId::of::<Children>()) {
+        registration.insert(InspectorEguiImpl::new(
+            hierarchy_links_ui::<Children>,
+            hierarchy_links_ui_readonly::<Children>,
+            hierarchy_links_ui_many::<Children>,
+        ));
+    }
+}
+
+/// The entities of [`Parent`] and [`Children`]
+trait HierarchyLinks: 'static {
+    fn linked(&self) -> &[Entity];
+}
+
+impl HierarchyLinks for Parent {
+    fn linked(&self) -> &[Entity] {
+        self.as_slice()
+    }
+}
+
+impl HierarchyLinks for Children {
+    fn linked(&self) -> &[Entity] {
+        self
+    }
+}
+
+fn hierarchy_links_ui<T: HierarchyLinks>(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    options: &dyn Any,
+    id: egui::Id,
+    env: InspectorUi<'_, '_>,
+) -> bool {
+    hierarchy_links_ui_readonly::<T>(value, ui, options, id, env);
+    false
+}
+
+fn hierarchy_links_ui_readonly<T: HierarchyLinks>(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) {
+    let Some(value) = value.downcast_ref::<T>() else {
+        return;
+    };
+    ui.vertical(|ui| {
+        for &entity in value.linked() {
+            entity_link(ui, &mut env, entity);
+        }
+    });
+}
+
+fn hierarchy_links_ui_many<T: HierarchyLinks>(
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+) -> bool {
+    let mut linked = values
+        .iter_mut()
+        .filter_map(|value| projector(*value).try_downcast_ref::<T>().map(T::linked));
+    let first = linked.next().unwrap_or_default();
+    match linked.all(|other| other == first) {
+        true => {
+            ui.vertical(|ui| {
+                for &entity in first {
+                    entity_link(ui, &mut env, entity);
+                }
+            });
+        }
+        false => {
+            ui.weak("Different entities");
+        }
+    }
+    false
+}
+
+fn entity_ui(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) -> bool {
+    let Some(entity) = value.downcast_mut::<Entity>() else {
+        return false;
+    };
+    match field_ui(ui, id, &mut env, Some(*entity)) {
+        Some(new) if new != *entity => {
+            *entity = new;
+            true
+        }
+        _ => false,
+    }
+}
+
+fn entity_ui_readonly(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) {
+    let Some(&entity) = value.downcast_ref::<Entity>() else {
+        return;
+    };
+    entity_link(ui, &mut env, entity);
+}
+
+fn entity_ui_many(
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+) -> bool {
+    let entities: Vec<Entity> = values
+        .iter_mut()
+        .filter_map(|value| projector(*value).try_downcast_ref::<Entity>().copied())
+        .collect();
+    let same = entities
+        .first()
+        .filter(|&&first| entities.iter().all(|&entity| entity == first))
+        .copied();
+
+    let Some(new) = field_ui(ui, id, &mut env, same) else {
+        return false;
+    };
+    for value in values.iter_mut() {
+        if let Some(entity) = projector(*value).try_downcast_mut::<Entity>() {
+            *entity = new;
+        }
+    }
+    true
+}
+
+/// Returns the entity the field was set to, `entity` is `None` for differing values
+fn field_ui(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    env: &mut InspectorUi<'_, '_>,
+    entity: Option<Entity>,
+) -> Option<Entity> {
+    let mut new = None;
+    let row = ui.horizontal(|ui| {
+        match entity {
+            Some(entity) => entity_link(ui, env, entity),
+            None => {
+                ui.weak("Different entities");
+            }
+        }
+        new = eyedropper_ui(ui, id, env);
+    });
+
+    // the inspected entity is locked
+    if !ui.is_enabled() {
+        return None;
+    }
+    if row
+        .response
+        .dnd_hover_payload::<DraggedEntities>()
+        .is_some()
+    {
+        ui.painter().rect_stroke(
+            row.response.rect,
+            2.0,
+            ui.visuals().selection.stroke,
+            egui::StrokeKind::Outside,
+        );
+    }
+    if let Some(dropped) = row.response.dnd_release_payload::<DraggedEntities>() {
+        new = dropped.0.first().copied();
+    }
+
+    new
+}
+
+/// Toggles waiting for a pick for the field `id`, returns the picked entity once there is one
+fn eyedropper_ui(ui: &mut egui::Ui, id: egui::Id, env: &mut InspectorUi<'_, '_>) -> Option<Entity> {
+    let mut picker = env
+        .context
+        .world
+        .as_mut()?
+        .get_resource_mut::<EntityPicker>()
+        .ok()?;
+
+    let picking = picker.target == Some(id);
+    let eyedropper = ui
+        .selectable_label(picking, "🎯")
+        .on_hover_text("Pick an entity in the hierarchy or a viewport");
+    if eyedropper.clicked() {
+        picker.target = (!picking).then_some(id);
+    }
+    if picking && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+        picker.target = None;
+    }
+
+    match picker.picked {
+        Some((picked_id, entity)) if picked_id == id => {
+            picker.picked = None;
+            Some(entity)
+        }
+        _ => None,
+    }
+}
+
+/// The name of `entity`, selecting and revealing it in the hierarchy when clicked
+fn entity_link(ui: &mut egui::Ui, env: &mut InspectorUi<'_, '_>, entity: Entity) {
+    let label = match env.context.world.as_ref() {
+        Some(world) => entity_name(world, entity),
+        None => entity.to_string(),
+    };
+    let response = ui.link(label).on_hover_text("Select in the hierarchy");
+    if !response.clicked() {
+        return;
+    }
+    let reveal = move |world: &mut World| hierarchy::reveal_entity(world, entity);
+    match env.context.queue.as_deref_mut() {
+        Some(queue) => queue.push(reveal),
+        None => warn!("Can't select {entity} from here"),
+    }
+}
+
+fn entity_name(world: &RestrictedWorldView, entity: Entity) -> String {
+    if entity == Entity::PLACEHOLDER {
+        return "None".to_owned();
+    }
+    let Some(cell) = world.world().get_entity(entity) else {
+        return format!("{entity} (despawned)");
+    };
+    if world.allows_access_to_component((entity, TypeId::of::<Name>())) {
+        // SAFETY: the view allows reading `Name` of `entity`, and the reference isn't kept
+        if let Some(name) = unsafe { cell.get::<Name>() } {
+            return format!("{} ({entity})", name.as_str());
+        }
+    }
+    entity.to_string()
+}
@@ -106,7 +106,12 @@ impl EditorWindow for GizmosWindow {
     //             // todo: not ignore errors
     //             world.run_system_once(reset_gizmos_selected_state).ok();
 
-    //             let selected_entities = hierarchy_state.selected.iter();
+    //             // locked entities and their descendants can't be moved
+    //             let selected_entities = hierarchy_state
+    //                 .selected
+    //                 .iter()
+    //                 .filter(|&entity| !crate::hierarchy::is_locked(world, entity))
+    //                 .collect::<Vec<_>>();
     //             for entity in selected_entities {
     //                 if let Ok(mut entity) = world.get_entity_mut(entity) {
     //                     entity.insert(EntityShouldShowGizmo);
@@ -0,0 +1,431 @@
+//! `Handle<T>` fields in the inspector, like the mesh of a `Mesh3d` or the image of a `Sprite`.
+//!
+//! They get a picker to reassign them to another loaded asset, with thumbnails for images, or to
+//! one loaded from a path. Assets dragged from the assets window can be dropped onto the picker,
+//! and its `Presets` menu applies presets to the asset the field points to. The asset itself is
+//! still shown below the picker.
+//!
+//! The ui is registered in the [`AppTypeRegistry`] for the builtin asset types, see
+//! [`ReflectHandlePicker`] for others.
+
+use std::any::{Any, TypeId};
+
+use bevy::asset::{LoadState, UntypedAssetId};
+use bevy::ecs::world::CommandQueue;
+use bevy::prelude::*;
+use bevy::reflect::{FromType, TypeRegistry};
+use bevy_inspector_egui::bevy_egui::EguiUserTextures;
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::inspector_egui_impls::InspectorEguiImpl;
+use bevy_inspector_egui::reflect_inspector::{Context, InspectorUi, ProjectorReflect};
+use bevy_inspector_egui::restricted_world_view::RestrictedWorldView;
+
+use crate::assets::DraggedAsset;
+use crate::presets::{self, PresetAction, Presets};
+
+/// Size of the image thumbnails in the picker
+const THUMBNAIL_SIZE: f32 = 32.0;
+
+/// Image thumbnails added to egui by open pickers, removed once no picker shows them
+#[derive(Resource, Default)]
+struct Thumbnails {
+    added: Vec<Handle<Image>>,
+    /// whether a picker showed thumbnails since the last [`remove_unused_thumbnails`]
+    shown: bool,
+}
+
+/// Shows the picker on the `Handle<A>` fields of the asset type `A`.
+///
+/// Inserted for the builtin asset types, add it for others with
+/// `app.register_type_data::<MyAsset, ReflectHandlePicker>()`.
+#[derive(Clone)]
+pub struct ReflectHandlePicker {
+    register: fn(&mut TypeRegistry),
+}
+
+impl<A: Asset> FromType<A> for ReflectHandlePicker {
+    fn from_type() -> Self {
+        ReflectHandlePicker {
+            register: register_picker::<A>,
+        }
+    }
+}
+
+/// The ui `Handle<A>` had before the picker, like the preview of images, shown below the picker
+#[derive(Clone)]
+struct PreviousHandleUi(Option<InspectorEguiImpl>);
+
+fn register_picker<A: Asset>(type_registry: &mut TypeRegistry) {
+    type_registry.register::<Handle<A>>();
+    let Some(registration) = type_registry.get_mut(TypeId::of::<Handle<A>>()) else {
+        return;
+    };
+    // already replaced, the ui in the registration is the picker itself
+    if registration.data::<PreviousHandleUi>().is_some() {
+        return;
+    }
+    let previous = registration.data::<InspectorEguiImpl>().cloned();
+    registration.insert(PreviousHandleUi(previous));
+    registration.insert(InspectorEguiImpl::new(
+        handle_ui::<A>,
+        handle_ui_readonly::<A>,
+        handle_ui_many::<A>,
+    ));
+}
+
+/// Inserts [`ReflectHandlePicker`] for the builtin asset types that are registered, and the picker
+/// for every asset type that has it. Runs after the default ui of handles is registered.
+pub(crate) fn register_handle_pickers(world: &mut World) {
+    fn insert<A: Asset>(type_registry: &mut TypeRegistry) {
+        if let Some(registration) = type_registry.get_mut(TypeId::of::<A>()) {
+            registration.insert(<ReflectHandlePicker as FromType<A>>::from_type());
+        }
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let mut type_registry = type_registry.write();
+    insert::<Mesh>(&mut type_registry);
+    insert::<Image>(&mut type_registry);
+    insert::<StandardMaterial>(&mut type_registry);
+    insert::<ColorMaterial>(&mut type_registry);
+    insert::<TextureAtlasLayout>(&mut type_registry);
+    insert::<Font>(&mut type_registry);
+    insert::<Scene>(&mut type_registry);
+
+    let pickers: Vec<fn(&mut TypeRegistry)> = type_registry
+        .iter()
+        .filter_map(|registration| registration.data::<ReflectHandlePicker>())
+        .map(|picker| picker.register)
+        .collect();
+    for register in pickers {
+        register(&mut type_registry);
+    }
+}
+
+fn handle_ui<A: Asset>(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    options: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) -> bool {
+    let Some(handle) = value.downcast_mut::<Handle<A>>() else {
+        return false;
+    };
+    let mut changed = false;
+    if let Some(new) = picker_ui::<A>(ui, id, &mut env, Some(handle.id())) {
+        *handle = new;
+        changed = true;
+    }
+
+    let type_registry = env.type_registry;
+    match type_registry.get_type_data::<PreviousHandleUi>(TypeId::of::<Handle<A>>()) {
+        Some(PreviousHandleUi(Some(previous))) => {
+            changed |= previous.execute(handle, ui, options, id, env);
+        }
+        _ => {
+            let short_circuit = env.short_circuit;
+            changed |= short_circuit(&mut env, handle, ui, id, options).unwrap_or(false);
+        }
+    }
+    changed
+}
+
+fn handle_ui_readonly<A: Asset>(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    options: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) {
+    let Some(handle) = value.downcast_ref::<Handle<A>>() else {
+        return;
+    };
+    if let Some(asset_server) = env
+        .context
+        .world
+        .as_mut()
+        .and_then(|world| world.get_resource_mut::<AssetServer>().ok())
+    {
+        ui.label(asset_label(&asset_server, handle.id().untyped()));
+    }
+
+    let type_registry = env.type_registry;
+    match type_registry.get_type_data::<PreviousHandleUi>(TypeId::of::<Handle<A>>()) {
+        Some(PreviousHandleUi(Some(previous))) => {
+            previous.execute_readonly(handle, ui, options, id, env);
+        }
+        _ => {
+            let short_circuit_readonly = env.short_circuit_readonly;
+            short_circuit_readonly(&mut env, handle, ui, id, options);
+        }
+    }
+}
+
+fn handle_ui_many<A: Asset>(
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+) -> bool {
+    let ids: Vec<AssetId<A>> = values
+        .iter_mut()
+        .filter_map(|value| projector(*value).try_downcast_ref::<Handle<A>>())
+        .map(Handle::id)
+        .collect();
+    let same = ids
+        .first()
+        .filter(|&&first| ids.iter().all(|&id| id == first))
+        .copied();
+
+    let Some(new) = picker_ui::<A>(ui, id, &mut env, same) else {
+        return false;
+    };
+    for value in values.iter_mut() {
+        if let Some(handle) = projector(*value).try_downcast_mut::<Handle<A>>() {
+            *handle = new.clone();
+        }
+    }
+    true
+}
+
+/// Returns the handle the field was set to, `current` is `None` for differing assets
+fn picker_ui<A: Asset>(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    env: &mut InspectorUi<'_, '_>,
+    current: Option<AssetId<A>>,
+) -> Option<Handle<A>> {
+    let Context {
+        world: Some(world),
+        queue,
+    } = &mut *env.context
+    else {
+        return None;
+    };
+    let asset_server = world.get_resource_mut::<AssetServer>().ok()?.clone();
+
+    let mut new = None;
+    let text = match current {
+        Some(current) => asset_label(&asset_server, current.untyped()),
+        None => "Different assets".to_owned(),
+    };
+    let row = ui.horizontal(|ui| {
+        ui.menu_button(text, |ui| {
+            new = picker_menu::<A>(ui, id, world, &asset_server, current);
+        });
+        if let Some(LoadState::Failed(error)) =
+            current.and_then(|current| asset_server.get_load_state(current))
+        {
+            ui.label("⚠")
+                .on_hover_text(format!("Failed to load: {error}"));
+        }
+        if let Some(current) = current {
+            presets_ui::<A>(ui, world, queue.as_deref_mut(), current);
+        }
+    });
+
+    // the inspected entity is locked
+    if !ui.is_enabled() {
+        return None;
+    }
+    let response = row.response;
+    let dragged = response
+        .dnd_hover_payload::<DraggedAsset>()
+        .filter(|dragged| dragged.0.type_id() == TypeId::of::<A>());
+    if dragged.is_some() {
+        ui.painter().rect_stroke(
+            response.rect,
+            2.0,
+            ui.visuals().selection.stroke,
+            egui::StrokeKind::Outside,
+        );
+    }
+    if let Some(dropped) = response.dnd_release_payload::<DraggedAsset>() {
+        match dropped.0.try_typed::<A>() {
+            Ok(dropped) => new = Some(strong_handle(world, &asset_server, dropped)),
+            Err(_) => warn!(
+                "Can't assign a different type of asset to a {}",
+                A::type_path()
+            ),
+        }
+    }
+
+    new
+}
+
+/// The path field to load an asset, and the loaded assets of type `A` to pick from
+fn picker_menu<A: Asset>(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    world: &mut RestrictedWorldView,
+    asset_server: &AssetServer,
+    current: Option<AssetId<A>>,
+) -> Option<Handle<A>> {
+    let mut new = None;
+    let data_id = id.with("handle_picker");
+    let (mut search, mut path) = ui
+        .data(|d| d.get_temp::<(String, String)>(data_id))
+        .unwrap_or_default();
+
+    ui.horizontal(|ui| {
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut path)
+                .hint_text("path/to/asset")
+                .desired_width(160.0),
+        );
+        let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let load = ui
+            .add_enabled(!path.trim().is_empty(), egui::Button::new("Load"))
+            .on_hover_text("Load from the asset folder");
+        if (load.clicked() || enter) && !path.trim().is_empty() {
+            new = Some(asset_server.load::<A>(path.trim().to_owned()));
+            ui.close_menu();
+        }
+    });
+    ui.separator();
+    ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search"));
+
+    let ids: Vec<AssetId<A>> = match world.get_resource_mut::<Assets<A>>() {
+        Ok(assets) => assets.ids().collect(),
+        Err(_) => Vec::new(),
+    };
+    let search_lowercase = search.to_lowercase();
+    let mut assets: Vec<(AssetId<A>, String)> = ids
+        .into_iter()
+        .map(|id| (id, asset_label(asset_server, id.untyped())))
+        .filter(|(_, label)| label.to_lowercase().contains(&search_lowercase))
+        .collect();
+    assets.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    let mut picked = None;
+    egui::ScrollArea::vertical()
+        .max_height(300.0)
+        .show(ui, |ui| {
+            if assets.is_empty() {
+                ui.weak("No assets");
+            }
+            for (id, label) in assets {
+                let clicked = ui
+                    .horizontal(|ui| {
+                        thumbnail_ui(ui, world, id.untyped());
+                        ui.selectable_label(current == Some(id), label).clicked()
+                    })
+                    .inner;
+                if clicked {
+                    picked = Some(id);
+                    ui.close_menu();
+                }
+            }
+        });
+    if let Some(id) = picked {
+        new = Some(strong_handle(world, asset_server, id));
+    }
+
+    ui.data_mut(|d| d.insert_temp(data_id, (search, path)));
+    new
+}
+
+/// The presets of the asset the field points to, applied once the inspector is done drawing
+fn presets_ui<A: Asset>(
+    ui: &mut egui::Ui,
+    world: &mut RestrictedWorldView,
+    queue: Option<&mut CommandQueue>,
+    asset_id: AssetId<A>,
+) {
+    let Ok(presets) = world.get_resource_mut::<Presets>() else {
+        return;
+    };
+    let type_path = A::type_path();
+    let Some(action) = presets::presets_menu(ui, &presets, type_path, true) else {
+        return;
+    };
+    let asset_id = asset_id.untyped();
+    let command = move |world: &mut World| match action {
+        PresetAction::Apply(name) => presets::apply_asset_preset(world, asset_id, type_path, &name),
+        PresetAction::Save(name) => presets::save_asset_preset(world, asset_id, &name),
+    };
+    match queue {
+        Some(queue) => queue.push(command),
+        None => warn!("Can't apply presets to {asset_id} from here"),
+    }
+}
+
+/// A strong handle to `id`, so the asset stays alive once it is only used by the field.
+///
+/// Asks the asset server, then the `Assets<A>`, and falls back to a weak handle.
+fn strong_handle<A: Asset>(
+    world: &mut RestrictedWorldView,
+    asset_server: &AssetServer,
+    id: AssetId<A>,
+) -> Handle<A> {
+    if let Some(handle) = asset_server.get_id_handle(id) {
+        return handle;
+    }
+    if let Some(handle) = world
+        .get_resource_mut::<Assets<A>>()
+        .ok()
+        .and_then(|mut assets| assets.get_strong_handle(id))
+    {
+        return handle;
+    }
+
+    debug!("No strong handle to {id}, assigning a weak one");
+    Handle::Weak(id)
+}
+
+/// The path of the asset, or its id for assets that weren't loaded from a file
+pub fn asset_label(asset_server: &AssetServer, id: UntypedAssetId) -> String {
+    match asset_server.get_path(id) {
+        Some(path) => path.to_string(),
+        None => id.to_string(),
+    }
+}
+
+fn thumbnail_ui(ui: &mut egui::Ui, world: &mut RestrictedWorldView, id: UntypedAssetId) {
+    let Ok(id) = id.try_typed::<Image>() else {
+        return;
+    };
+    let Ok(mut textures) = world.get_resource_mut::<EguiUserTextures>() else {
+        return;
+    };
+    let handle = Handle::Weak(id);
+    let (texture, added) = match textures.image_id(&handle) {
+        Some(texture) => (texture, None),
+        None => (textures.add_image(handle.clone()), Some(handle)),
+    };
+    let Ok(mut thumbnails) = world.get_resource_mut::<Thumbnails>() else {
+        return;
+    };
+    thumbnails.added.extend(added);
+    thumbnails.shown = true;
+    ui.image(egui::load::SizedTexture::new(
+        texture,
+        [THUMBNAIL_SIZE, THUMBNAIL_SIZE],
+    ));
+}
+
+/// Removes the thumbnails from egui once the pickers showing them are closed
+fn remove_unused_thumbnails(
+    mut thumbnails: ResMut<Thumbnails>,
+    textures: Option<ResMut<EguiUserTextures>>,
+) {
+    if std::mem::take(&mut thumbnails.shown) {
+        return;
+    }
+    let Some(mut textures) = textures else {
+        return;
+    };
+    for handle in thumbnails.added.drain(..) {
+        textures.remove_image(&handle);
+    }
+}
+
+pub(crate) fn setup(app: &mut App) {
+    app.init_resource::<Thumbnails>().add_systems(
+        PostUpdate,
+        remove_unused_thumbnails.after(bevy_editor_pls_core::EditorSet::UI),
+    );
+}
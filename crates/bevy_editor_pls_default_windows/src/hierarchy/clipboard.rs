@@ -0,0 +1,97 @@
+//! Copying entities to the clipboard as scene RON, so they can be pasted into another
+//! running instance or shared as text.
+
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::reflect::serde::ReflectSerializer;
+use bevy::scene::ron;
+use bevy::scene::serde::SceneDeserializer;
+
+use super::duplicate::{collect_subtrees, parent_of, unreflected_components};
+
+/// Serializes `entities` and their children to scene RON.
+///
+/// Components that aren't reflected or can't be serialized are left out and logged.
+/// References to entities that aren't copied along are lost when pasting.
+pub fn copy_entities(world: &World, entities: &[Entity]) -> Result<String, ron::Error> {
+    let (roots, entities) = collect_subtrees(world, entities);
+    let mut skipped = unreflected_components(world, &entities);
+
+    // `Children` is rebuilt from `Parent` when pasting, it may reference editor internals
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .deny_component::<Children>()
+        .extract_entities(entities.into_iter())
+        .build();
+
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    for scene_entity in &mut scene.entities {
+        let is_root = roots.contains(&scene_entity.entity);
+        scene_entity.components.retain(|component| {
+            if is_root && component.represents::<Parent>() {
+                return false;
+            }
+
+            let serializer = ReflectSerializer::new(component.as_partial_reflect(), &type_registry);
+            let serializable = ron::to_string(&serializer).is_ok();
+            if !serializable {
+                let name = pretty_type_name::pretty_type_name_str(component.reflect_type_path());
+                if !skipped.contains(&name) {
+                    skipped.push(name);
+                }
+            }
+            serializable
+        });
+    }
+
+    if !skipped.is_empty() {
+        warn!(
+            "Copied entities without the components that can't be serialized: {}",
+            skipped.join(", ")
+        );
+    }
+
+    scene.serialize(&type_registry)
+}
+
+/// Spawns the entities serialized by [`copy_entities`] as children of `parent`,
+/// returning the pasted entities that were copied without their parent.
+pub fn paste_entities(
+    world: &mut World,
+    text: &str,
+    parent: Option<Entity>,
+) -> Result<Vec<Entity>, Box<dyn std::error::Error + Send + Sync>> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let mut scene: DynamicScene = ron::Options::default().from_str_seed(
+        text,
+        SceneDeserializer {
+            type_registry: &type_registry.read(),
+        },
+    )?;
+    // pasting entities shouldn't overwrite anything else
+    scene.resources.clear();
+
+    let mut entity_map = EntityHashMap::default();
+    scene.write_to_world_with(world, &mut entity_map, &type_registry)?;
+
+    let mut roots = Vec::new();
+    for scene_entity in &scene.entities {
+        let entity = entity_map[&scene_entity.entity];
+        // `Parent` was inserted through reflection, `Children` still has to be updated
+        match parent_of(world, entity) {
+            Some(pasted_parent) if world.get_entity(pasted_parent).is_ok() => {
+                world.entity_mut(pasted_parent).add_child(entity);
+            }
+            stale_parent => {
+                if stale_parent.is_some() {
+                    world.entity_mut(entity).remove::<Parent>();
+                }
+                if let Some(parent) = parent {
+                    world.entity_mut(parent).add_child(entity);
+                }
+                roots.push(entity);
+            }
+        }
+    }
+
+    Ok(roots)
+}
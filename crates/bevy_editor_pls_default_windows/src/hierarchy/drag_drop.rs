@@ -0,0 +1,301 @@
+//! Reparenting and reordering entities by dragging hierarchy rows.
+//!
+//! The rows are drawn by `bevy_inspector_egui`, which doesn't hand out their responses,
+//! so the row layout is recorded while drawing and the pointer is tracked by hand.
+//!
+//! - drop onto a row to make the dragged entities its children
+//! - drop between two rows to move them next to that row in its parent's [`Children`]
+//! - drop below the last row to unparent them
+//!
+//! The world transform is kept, unless `Alt` is held while dropping.
+//!
+//! The dragged entities are also an egui drag and drop payload, [`DraggedEntities`], so they can be
+//! dropped outside the hierarchy.
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use bevy_inspector_egui::egui;
+
+/// distance in egui points the pointer has to move before a press turns into a drag
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Position of an entity row in the hierarchy, recorded before the row is drawn
+pub(crate) struct Row {
+    entity: Entity,
+    top_left: egui::Pos2,
+    right: f32,
+}
+
+impl Row {
+    pub(crate) fn new(ui: &egui::Ui, entity: Entity) -> Self {
+        Row {
+            entity,
+            top_left: ui.cursor().min,
+            right: ui.max_rect().right(),
+        }
+    }
+}
+
+/// Entities dragged out of the hierarchy
+pub struct DraggedEntities(pub Vec<Entity>);
+
+#[derive(Clone)]
+struct DragState {
+    entities: Vec<Entity>,
+    origin: egui::Pos2,
+    active: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DropTarget {
+    Onto(Entity),
+    Before(Entity),
+    After(Entity),
+    Root,
+}
+
+/// Area covered by each row, up to the next row
+pub(crate) fn row_rects(ui: &egui::Ui, rows: &[Row]) -> Vec<(Entity, egui::Rect)> {
+    let row_height = ui.spacing().interact_size.y;
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let height = rows
+                .get(i + 1)
+                .map_or(row_height, |next| next.top_left.y - row.top_left.y)
+                .clamp(0.0, row_height + ui.spacing().item_spacing.y);
+            let rect = egui::Rect::from_min_max(
+                row.top_left,
+                egui::pos2(row.right, row.top_left.y + height),
+            );
+            (row.entity, rect)
+        })
+        .collect()
+}
+
+pub(crate) fn drag_drop_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    selected: &SelectedEntities,
+    rects: &[(Entity, egui::Rect)],
+) {
+    let id = ui.id().with("hierarchy_drag_drop");
+
+    let (pressed, down, press_origin, pointer, keep_local) = ui.input(|i| {
+        (
+            i.pointer.primary_pressed(),
+            i.pointer.primary_down(),
+            i.pointer.press_origin(),
+            i.pointer.latest_pos(),
+            i.modifiers.alt,
+        )
+    });
+
+    if pressed {
+        let pressed_row = press_origin.and_then(|origin| {
+            rects
+                .iter()
+                .find(|(_, rect)| rect.contains(origin))
+                .map(|&(entity, _)| (entity, origin))
+        });
+        if let Some((entity, origin)) = pressed_row {
+            // dragging a selected row takes the whole selection with it
+            let entities = if selected.contains(entity) {
+                rects
+                    .iter()
+                    .map(|&(entity, _)| entity)
+                    .filter(|&entity| selected.contains(entity))
+                    .collect()
+            } else {
+                vec![entity]
+            };
+            let state = DragState {
+                entities,
+                origin,
+                active: false,
+            };
+            ui.data_mut(|d| d.insert_temp(id, state));
+        }
+    }
+
+    let Some(mut state) = ui.data(|d| d.get_temp::<DragState>(id)) else {
+        return;
+    };
+    let Some(pointer) = pointer else {
+        return;
+    };
+
+    if !state.active {
+        if down && state.origin.distance(pointer) > DRAG_THRESHOLD {
+            // something else, like the rename text field, already owns this drag
+            if ui.ctx().dragged_id().is_some() {
+                ui.data_mut(|d| d.remove::<DragState>(id));
+                return;
+            }
+            state.active = true;
+            ui.data_mut(|d| d.insert_temp(id, state.clone()));
+            egui::DragAndDrop::set_payload(ui.ctx(), DraggedEntities(state.entities.clone()));
+        } else {
+            if !down {
+                ui.data_mut(|d| d.remove::<DragState>(id));
+            }
+            return;
+        }
+    }
+
+    let area = ui.clip_rect();
+    let target = area
+        .contains(pointer)
+        .then(|| drop_target(rects, pointer))
+        .filter(|&target| is_valid_target(world, &state.entities, target));
+
+    if down {
+        ui.ctx().set_cursor_icon(match target {
+            Some(_) => egui::CursorIcon::Grabbing,
+            None => egui::CursorIcon::NoDrop,
+        });
+        paint_drop_target(ui, rects, area, target);
+        return;
+    }
+
+    ui.data_mut(|d| d.remove::<DragState>(id));
+    if let Some(target) = target {
+        apply_drop(world, &state.entities, target, keep_local);
+    }
+}
+
+fn drop_target(rects: &[(Entity, egui::Rect)], pointer: egui::Pos2) -> DropTarget {
+    let row = rects
+        .iter()
+        .find(|(_, rect)| (rect.top()..rect.bottom()).contains(&pointer.y));
+    let Some(&(entity, rect)) = row else {
+        return DropTarget::Root;
+    };
+
+    let t = (pointer.y - rect.top()) / rect.height();
+    if t < 0.25 {
+        DropTarget::Before(entity)
+    } else if t > 0.75 {
+        DropTarget::After(entity)
+    } else {
+        DropTarget::Onto(entity)
+    }
+}
+
+/// An entity can't become a child of itself or of one of its descendants
+fn is_valid_target(world: &World, dragged: &[Entity], target: DropTarget) -> bool {
+    let parent = match target {
+        DropTarget::Onto(entity) => Some(entity),
+        DropTarget::Before(entity) | DropTarget::After(entity) => {
+            if dragged.contains(&entity) {
+                return false;
+            }
+            parent_of(world, entity)
+        }
+        DropTarget::Root => None,
+    };
+
+    parent.map_or(true, |parent| {
+        !dragged.contains(&parent) && ancestors(world, parent).all(|a| !dragged.contains(&a))
+    })
+}
+
+fn parent_of(world: &World, entity: Entity) -> Option<Entity> {
+    world.get::<Parent>(entity).map(Parent::get)
+}
+
+fn ancestors(world: &World, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+    std::iter::successors(parent_of(world, entity), |&entity| parent_of(world, entity))
+}
+
+fn paint_drop_target(
+    ui: &egui::Ui,
+    rects: &[(Entity, egui::Rect)],
+    area: egui::Rect,
+    target: Option<DropTarget>,
+) {
+    let Some(target) = target else {
+        return;
+    };
+    let stroke = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+    let rect_of = |entity| {
+        rects
+            .iter()
+            .find(|&&(e, _)| e == entity)
+            .map(|&(_, rect)| rect)
+    };
+
+    let painter = ui.painter();
+    match target {
+        DropTarget::Onto(entity) => {
+            if let Some(rect) = rect_of(entity) {
+                painter.rect_stroke(rect, 2.0, stroke, egui::StrokeKind::Inside);
+            }
+        }
+        DropTarget::Before(entity) => {
+            if let Some(rect) = rect_of(entity) {
+                painter.hline(rect.x_range(), rect.top(), stroke);
+            }
+        }
+        DropTarget::After(entity) => {
+            if let Some(rect) = rect_of(entity) {
+                painter.hline(rect.x_range(), rect.bottom(), stroke);
+            }
+        }
+        DropTarget::Root => {
+            painter.rect_stroke(area.shrink(1.0), 2.0, stroke, egui::StrokeKind::Inside);
+        }
+    }
+}
+
+fn apply_drop(world: &mut World, dragged: &[Entity], target: DropTarget, keep_local: bool) {
+    // children of dragged entities move along with their parent, locked ones stay where they are
+    let dragged: Vec<Entity> = dragged
+        .iter()
+        .copied()
+        .filter(|&entity| world.get_entity(entity).is_ok())
+        .filter(|&entity| ancestors(world, entity).all(|a| !dragged.contains(&a)))
+        .filter(|&entity| !super::is_locked(world, entity))
+        .collect();
+
+    let (parent, sibling) = match target {
+        DropTarget::Onto(entity) => (Some(entity), None),
+        DropTarget::Before(entity) => (parent_of(world, entity), Some((entity, 0))),
+        DropTarget::After(entity) => (parent_of(world, entity), Some((entity, 1))),
+        DropTarget::Root => (None, None),
+    };
+    // a locked entity doesn't get new children either
+    if parent
+        .is_some_and(|parent| world.get_entity(parent).is_err() || super::is_locked(world, parent))
+    {
+        return;
+    }
+
+    for &entity in &dragged {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            continue;
+        };
+        match (parent, keep_local) {
+            (Some(parent), false) => entity.set_parent_in_place(parent),
+            (Some(parent), true) => entity.set_parent(parent),
+            (None, false) => entity.remove_parent_in_place(),
+            (None, true) => entity.remove_parent(),
+        };
+    }
+
+    // root entities are listed by id, so only children can be reordered
+    let (Some(parent), Some((sibling, offset))) = (parent, sibling) else {
+        return;
+    };
+    let Some(index) = world.get::<Children>(parent).and_then(|children| {
+        children
+            .iter()
+            .filter(|child| !dragged.contains(child))
+            .position(|&child| child == sibling)
+    }) else {
+        return;
+    };
+    world
+        .entity_mut(parent)
+        .insert_children(index + offset, &dragged);
+}
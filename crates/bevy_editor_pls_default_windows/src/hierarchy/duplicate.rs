@@ -0,0 +1,159 @@
+use std::any::TypeId;
+
+use bevy::ecs::entity::{EntityHashMap, EntityHashSet};
+use bevy::prelude::*;
+use bevy::render::sync_world::RenderEntity;
+use bevy::render::view::RenderLayers;
+
+use super::HideInEditor;
+use crate::cameras::is_editor_only;
+
+pub struct Duplicated {
+    /// copies of the duplicated entities, in the order they were given
+    pub entities: Vec<Entity>,
+    /// components that aren't reflected and could not be copied
+    pub skipped: Vec<String>,
+}
+
+/// Deep copies `entities` and their children through reflection.
+///
+/// Entity references between the copied entities are remapped to the copies,
+/// references to anything else keep pointing at the original entity.
+/// Copies are inserted right after their original in the parent's [`Children`].
+pub fn duplicate_entities(world: &mut World, entities: &[Entity]) -> Duplicated {
+    let (roots, originals) = collect_subtrees(world, entities);
+    let original_set: EntityHashSet = originals.iter().copied().collect();
+
+    let skipped = unreflected_components(world, &originals);
+
+    // the hierarchy is rebuilt below, so copies end up next to their originals
+    let scene = DynamicSceneBuilder::from_world(world)
+        .deny_component::<Parent>()
+        .deny_component::<Children>()
+        .extract_entities(originals.iter().copied())
+        .build();
+
+    let mut entity_map: EntityHashMap<Entity> = world
+        .iter_entities()
+        .map(|entity| entity.id())
+        .filter(|entity| !original_set.contains(entity))
+        .map(|entity| (entity, entity))
+        .collect();
+    if let Err(err) = scene.write_to_world(world, &mut entity_map) {
+        error!("Failed to duplicate entities: {}", err);
+        // copies spawned before the error would be left without parents and some components
+        for original in &originals {
+            if let Some(&copy) = entity_map.get(original) {
+                world.despawn(copy);
+            }
+        }
+        return Duplicated {
+            entities: Vec::new(),
+            skipped,
+        };
+    }
+
+    for &original in &originals {
+        let (Some(&copy), Some(parent)) = (entity_map.get(&original), parent_of(world, original))
+        else {
+            continue;
+        };
+
+        if original_set.contains(&parent) {
+            world.entity_mut(entity_map[&parent]).add_child(copy);
+        } else {
+            let index = world
+                .get::<Children>(parent)
+                .and_then(|children| children.iter().position(|&child| child == original))
+                .map_or(0, |index| index + 1);
+            world.entity_mut(parent).insert_children(index, &[copy]);
+        }
+    }
+
+    if !skipped.is_empty() {
+        warn!(
+            "Duplicated entities without the components that aren't reflected: {}",
+            skipped.join(", ")
+        );
+    }
+
+    Duplicated {
+        entities: roots
+            .iter()
+            .filter_map(|root| entity_map.get(root).copied())
+            .collect(),
+        skipped,
+    }
+}
+
+pub(crate) fn parent_of(world: &World, entity: Entity) -> Option<Entity> {
+    world.get::<Parent>(entity).map(Parent::get)
+}
+
+/// The entities in `entities` that aren't a descendant of another one, and all of their
+/// descendants, parents before children
+pub(crate) fn collect_subtrees(world: &World, entities: &[Entity]) -> (Vec<Entity>, Vec<Entity>) {
+    let roots: Vec<Entity> = entities
+        .iter()
+        .copied()
+        .filter(|&entity| world.get_entity(entity).is_ok())
+        .filter(|&entity| {
+            std::iter::successors(parent_of(world, entity), |&e| parent_of(world, e))
+                .all(|ancestor| !entities.contains(&ancestor))
+        })
+        .collect();
+
+    let mut subtrees = Vec::new();
+    for &root in &roots {
+        collect_subtree(world, root, &mut subtrees);
+    }
+    (roots, subtrees)
+}
+
+/// `entity` and its descendants, parents before children, leaving out editor internals
+fn collect_subtree(world: &World, entity: Entity, entities: &mut Vec<Entity>) {
+    entities.push(entity);
+
+    let Some(children) = world.get::<Children>(entity) else {
+        return;
+    };
+    for &child in children {
+        let internal = world.get::<HideInEditor>(child).is_some()
+            || is_editor_only(world.get::<RenderLayers>(child));
+        if !internal {
+            collect_subtree(world, child, entities);
+        }
+    }
+}
+
+/// The names of the components of `entities` that can't be copied through reflection, except for
+/// [`RenderEntity`], which copies get from [`SyncToRenderWorld`](bevy::render::sync_world::SyncToRenderWorld)
+pub(crate) fn unreflected_components(world: &World, entities: &[Entity]) -> Vec<String> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let recreated = [TypeId::of::<RenderEntity>()];
+
+    let mut skipped = Vec::new();
+    for &entity in entities {
+        for component_id in world.entity(entity).archetype().components() {
+            let Some(info) = world.components().get_info(component_id) else {
+                continue;
+            };
+            if info
+                .type_id()
+                .is_some_and(|type_id| recreated.contains(&type_id))
+            {
+                continue;
+            }
+            let reflected = info
+                .type_id()
+                .and_then(|type_id| type_registry.get(type_id))
+                .is_some_and(|registration| registration.data::<ReflectComponent>().is_some());
+
+            let name = pretty_type_name::pretty_type_name_str(info.name());
+            if !reflected && !skipped.contains(&name) {
+                skipped.push(name);
+            }
+        }
+    }
+    skipped
+}
@@ -0,0 +1,336 @@
+use std::any::TypeId;
+
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy::utils::HashSet;
+use bevy_inspector_egui::bevy_inspector::guess_entity_name;
+use bevy_inspector_egui::egui;
+use regex::Regex;
+
+use super::is_editor_internal;
+
+/// Narrows down the entities listed in the hierarchy
+#[derive(Clone)]
+pub struct HierarchyFilter {
+    pub name: String,
+    /// match [`HierarchyFilter::name`] as a regex instead of a case insensitive substring
+    pub regex: bool,
+    pub components: Vec<ComponentFilter>,
+    pub mode: FilterMode,
+    pub internal: InternalEntities,
+    component_search: String,
+    compiled: Option<(String, Result<Regex, regex::Error>)>,
+    cache: Option<FilterCache>,
+}
+
+impl Default for HierarchyFilter {
+    fn default() -> Self {
+        HierarchyFilter {
+            name: String::new(),
+            regex: false,
+            components: Vec::new(),
+            mode: FilterMode::Tree,
+            internal: InternalEntities::Hidden,
+            component_search: String::new(),
+            compiled: None,
+            cache: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    /// show matches together with their ancestors
+    Tree,
+    /// show only matches, as a flat list
+    Flat,
+    /// show matches as roots, with their children
+    Roots,
+}
+
+/// How editor internals, like editor windows, editor cameras and gizmo markers, are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalEntities {
+    Hidden,
+    Dimmed,
+    Shown,
+}
+
+/// Requires an entity to have (or not have) a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentFilter {
+    pub type_id: TypeId,
+    pub name: String,
+    pub with: bool,
+}
+
+/// Entities to show for an active [`HierarchyFilter`]
+#[derive(Default, Clone)]
+pub struct FilterResult {
+    pub matches: HashSet<Entity>,
+    /// matches and all of their ancestors
+    pub visible: HashSet<Entity>,
+}
+
+/// The settings of a [`HierarchyFilter`] that change its result
+#[derive(Clone, PartialEq)]
+struct FilterKey {
+    name: String,
+    regex: bool,
+    components: Vec<ComponentFilter>,
+    internal: InternalEntities,
+}
+
+/// The last [`FilterResult`], reused until the filter changes, entities are spawned, despawned or
+/// gain or lose components, or a [`Name`] or [`Parent`] changes
+#[derive(Clone)]
+struct FilterCache {
+    key: FilterKey,
+    archetypes: Vec<ArchetypeFingerprint>,
+    name_or_parent_changes: u64,
+    result: FilterResult,
+}
+
+/// The entity count and last entity of an archetype.
+///
+/// Despawning an entity moves the last entity of its archetype into its place, and spawning one
+/// appends it, so a despawn followed by a spawn into the same archetype changes the last entity
+/// even though the count stays the same.
+type ArchetypeFingerprint = (usize, Option<Entity>);
+
+fn archetype_fingerprints(world: &World) -> Vec<ArchetypeFingerprint> {
+    world
+        .archetypes()
+        .iter()
+        .map(|archetype| {
+            let last = archetype.entities().last().map(|entity| entity.id());
+            (archetype.len(), last)
+        })
+        .collect()
+}
+
+/// Counts the frames in which a [`Name`] or [`Parent`] was changed, added or removed, which can
+/// change the result of a [`HierarchyFilter`]
+#[derive(Resource, Default)]
+pub(crate) struct NameOrParentChanges(u64);
+
+pub(crate) fn track_name_or_parent_changes(
+    mut changes: ResMut<NameOrParentChanges>,
+    changed: Query<(), Or<(Changed<Name>, Changed<Parent>)>>,
+    mut removed_names: RemovedComponents<Name>,
+    mut removed_parents: RemovedComponents<Parent>,
+) {
+    let removed = removed_names.read().count() + removed_parents.read().count() > 0;
+    if removed || !changed.is_empty() {
+        changes.0 += 1;
+    }
+}
+
+impl HierarchyFilter {
+    pub fn is_active(&self) -> bool {
+        !self.name.is_empty() || !self.components.is_empty()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, type_registry: &TypeRegistry) {
+        ui.horizontal(|ui| {
+            let hint = if self.regex {
+                "Filter regex"
+            } else {
+                "Filter by name"
+            };
+            let regex_width = ui.spacing().interact_size.x;
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name)
+                    .hint_text(hint)
+                    .desired_width(ui.available_width() - 3.0 * regex_width),
+            );
+            ui.toggle_value(&mut self.regex, ".*")
+                .on_hover_text("Use a regular expression");
+            ui.menu_button("+", |ui| self.add_component_ui(ui, type_registry))
+                .response
+                .on_hover_text("Filter by component");
+            ui.menu_button("⚙", |ui| {
+                ui.label("Editor entities");
+                ui.radio_value(&mut self.internal, InternalEntities::Hidden, "Hidden");
+                ui.radio_value(&mut self.internal, InternalEntities::Dimmed, "Dimmed");
+                ui.radio_value(&mut self.internal, InternalEntities::Shown, "Shown");
+            })
+            .response
+            .on_hover_text("Editor windows, cameras and gizmo markers");
+        });
+
+        if let Some(Err(err)) = self.compiled_regex() {
+            ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+        }
+
+        let mut remove = None;
+        if !self.components.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for (i, filter) in self.components.iter_mut().enumerate() {
+                    let prefix = if filter.with { "with" } else { "without" };
+                    let response = ui
+                        .button(format!("{prefix} {}", filter.name))
+                        .on_hover_text("Click to invert, right click to remove");
+                    if response.clicked() {
+                        filter.with = !filter.with;
+                    }
+                    if response.secondary_clicked() {
+                        remove = Some(i);
+                    }
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.components.remove(i);
+        }
+
+        if self.is_active() {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.mode, FilterMode::Tree, "Tree")
+                    .on_hover_text("Show matches with their ancestors");
+                ui.radio_value(&mut self.mode, FilterMode::Flat, "Flat")
+                    .on_hover_text("Show only matches");
+                ui.radio_value(&mut self.mode, FilterMode::Roots, "Roots")
+                    .on_hover_text("Show matches with their children");
+                if ui.button("Clear").clicked() {
+                    self.name.clear();
+                    self.components.clear();
+                }
+            });
+        }
+    }
+
+    fn add_component_ui(&mut self, ui: &mut egui::Ui, type_registry: &TypeRegistry) {
+        ui.add(egui::TextEdit::singleline(&mut self.component_search).hint_text("Search"));
+        let search = self.component_search.to_lowercase();
+
+        let mut components: Vec<_> = type_registry
+            .iter()
+            .filter(|registration| registration.data::<ReflectComponent>().is_some())
+            .map(|registration| {
+                let path = registration.type_info().type_path_table();
+                (registration.type_id(), path.short_path())
+            })
+            .filter(|(_, name)| name.to_lowercase().contains(&search))
+            .collect();
+        components.sort_by_key(|&(_, name)| name);
+
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for (type_id, name) in components {
+                    if ui.button(name).clicked() {
+                        self.components.push(ComponentFilter {
+                            type_id,
+                            name: name.to_owned(),
+                            with: true,
+                        });
+                        ui.close_menu();
+                    }
+                }
+            });
+    }
+
+    fn compiled_regex(&mut self) -> Option<&Result<Regex, regex::Error>> {
+        if !self.regex || self.name.is_empty() {
+            return None;
+        }
+        if self
+            .compiled
+            .as_ref()
+            .map_or(true, |(source, _)| *source != self.name)
+        {
+            self.compiled = Some((self.name.clone(), Regex::new(&self.name)));
+        }
+        self.compiled.as_ref().map(|(_, regex)| regex)
+    }
+
+    /// The entities matching the filter, recomputed only when the filter or the world changed
+    pub fn apply(&mut self, world: &mut World) -> &FilterResult {
+        let key = FilterKey {
+            name: self.name.clone(),
+            regex: self.regex,
+            components: self.components.clone(),
+            internal: self.internal,
+        };
+        let archetypes = archetype_fingerprints(world);
+        let name_or_parent_changes = world
+            .get_resource::<NameOrParentChanges>()
+            .map_or(0, |changes| changes.0);
+        let outdated = self.cache.as_ref().map_or(true, |cache| {
+            cache.key != key
+                || cache.archetypes != archetypes
+                || cache.name_or_parent_changes != name_or_parent_changes
+        });
+        if outdated {
+            let result = self.compute(world);
+            self.cache = Some(FilterCache {
+                key,
+                archetypes,
+                name_or_parent_changes,
+                result,
+            });
+        }
+        &self.cache.as_ref().unwrap().result
+    }
+
+    fn compute(&mut self, world: &mut World) -> FilterResult {
+        let mut components = Vec::new();
+        for filter in &self.components {
+            match world.components().get_id(filter.type_id) {
+                Some(id) => components.push((id, filter.with)),
+                // no entity can have a component that was never registered
+                None if filter.with => return FilterResult::default(),
+                None => {}
+            }
+        }
+
+        let name = self.name.to_lowercase();
+        let regex = match self.compiled_regex() {
+            Some(Ok(regex)) => Some(regex.clone()),
+            // an invalid regex matches nothing
+            Some(Err(_)) => return FilterResult::default(),
+            None => None,
+        };
+
+        let mut result = FilterResult::default();
+
+        let mut query = world.query::<Entity>();
+        let entities: Vec<Entity> = query.iter(world).collect();
+        for entity in entities {
+            if self.internal == InternalEntities::Hidden && is_editor_internal(world, entity) {
+                continue;
+            }
+            let entity_ref = world.entity(entity);
+            let has_components = components
+                .iter()
+                .all(|&(id, with)| entity_ref.contains_id(id) == with);
+            if !has_components {
+                continue;
+            }
+
+            if !name.is_empty() || regex.is_some() {
+                let entity_name = guess_entity_name(world, entity);
+                let matches = match &regex {
+                    Some(regex) => regex.is_match(&entity_name),
+                    None => entity_name.to_lowercase().contains(&name),
+                };
+                if !matches {
+                    continue;
+                }
+            }
+
+            result.matches.insert(entity);
+            result.visible.insert(entity);
+            let mut current = entity;
+            while let Some(parent) = world.get::<Parent>(current) {
+                current = parent.get();
+                if !result.visible.insert(current) {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,122 @@
+//! Selection highlight drawn with gizmos, for when wireframes aren't supported by the GPU
+//! or don't fit the scene, like sprites in 2D.
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy_editor_pls_core::Editor;
+use bevy_inspector_egui::bevy_egui::EguiContextSettings;
+use bevy_inspector_egui::egui;
+
+use super::HierarchyState;
+use crate::debug_settings::{DebugSettings, HighlightMode};
+
+/// Gizmos outlining the selected entities, see [`DebugSettings::highlight_mode`]
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct SelectionGizmos;
+
+pub(crate) fn setup(app: &mut App) {
+    app.insert_gizmo_config(
+        SelectionGizmos,
+        GizmoConfig {
+            line_width: 2.0,
+            // keep the outline visible through the surface of the selected mesh
+            depth_bias: -1.0,
+            ..default()
+        },
+    )
+    .add_systems(PostUpdate, draw_selection_outlines);
+}
+
+fn highlights_outline(editor: &Editor, debug: &DebugSettings) -> bool {
+    editor.active && debug.highlight_selected && debug.highlight_mode == HighlightMode::Outline
+}
+
+/// Outlines the [`Aabb`] of selected meshes and sprites. Flat bounds, like the ones of sprites,
+/// are drawn as a rectangle.
+fn draw_selection_outlines(
+    editor: Res<Editor>,
+    debug: Res<DebugSettings>,
+    states: Query<&HierarchyState>,
+    bounds: Query<(&Aabb, &GlobalTransform)>,
+    mut gizmos: Gizmos<SelectionGizmos>,
+) {
+    if !highlights_outline(&editor, &debug) {
+        return;
+    }
+
+    let selected = states.iter().flat_map(|state| state.selected.iter());
+    for (aabb, transform) in bounds.iter_many(selected) {
+        let bounds = Transform::from_translation(aabb.center.into())
+            .with_scale((aabb.half_extents * 2.0).into());
+        let transform = transform.mul_transform(bounds);
+
+        if aabb.half_extents.z == 0.0 {
+            let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+            let isometry = Isometry3d::new(translation, rotation);
+            gizmos.rect(isometry, scale.truncate(), debug.highlight_color);
+        } else {
+            gizmos.cuboid(transform, debug.highlight_color);
+        }
+    }
+}
+
+/// Outlines the selected UI nodes rendered by `camera_entity`.
+///
+/// UI is laid out in the viewport of its camera instead of the world, so it's painted
+/// over the camera window instead of using gizmos.
+pub(crate) fn ui_node_outlines(world: &mut World, camera_entity: Entity, ui: &egui::Ui) {
+    let mut state: SystemState<(
+        Res<Editor>,
+        Res<DebugSettings>,
+        Query<&EguiContextSettings>,
+        Query<&Camera>,
+        Query<&HierarchyState>,
+        Query<(Entity, &ComputedNode, &GlobalTransform)>,
+        Query<&TargetCamera>,
+        Query<&Parent>,
+        DefaultUiCamera,
+    )> = SystemState::new(world);
+    let (editor, debug, egui_settings, cameras, states, nodes, targets, parents, default_camera) =
+        state.get(world);
+
+    if !highlights_outline(&editor, &debug) {
+        return;
+    }
+    let Ok(camera) = cameras.get(camera_entity) else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_rect() else {
+        return;
+    };
+    let scale_factor = camera.target_scaling_factor().unwrap_or(1.0);
+    let egui_scale = egui_settings
+        .get(editor.window())
+        .map_or(1.0, |settings| settings.scale_factor);
+
+    let [r, g, b, a] = debug.highlight_color.to_srgba().to_u8_array();
+    let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+
+    let selected = states.iter().flat_map(|state| state.selected.iter());
+    for (entity, node, transform) in nodes.iter_many(selected) {
+        // only root nodes can have a `TargetCamera`
+        let root = parents.iter_ancestors(entity).last().unwrap_or(entity);
+        let target = match targets.get(root) {
+            Ok(target) => Some(target.entity()),
+            Err(_) => default_camera.get(),
+        };
+        if target != Some(camera_entity) {
+            continue;
+        }
+
+        // node layout is in physical pixels, relative to the viewport of the camera
+        let center = viewport.min + transform.translation().truncate() / scale_factor;
+        let size = node.size() / scale_factor;
+        let rect = egui::Rect::from_center_size(
+            egui::pos2(center.x, center.y) / egui_scale,
+            egui::vec2(size.x, size.y) / egui_scale,
+        );
+        ui.painter()
+            .rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Outside);
+    }
+}
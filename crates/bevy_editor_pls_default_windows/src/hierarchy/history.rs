@@ -0,0 +1,299 @@
+//! Back/forward navigation between previous selections of a hierarchy.
+
+use std::collections::VecDeque;
+
+use bevy::ecs::entity::Entities;
+use bevy::ecs::event::EventCursor;
+use bevy::prelude::*;
+use bevy_editor_pls_core::editor_window::{linked_state_mut, DefaultLink};
+use bevy_editor_pls_core::{Editor, EditorEvent};
+use bevy_inspector_egui::bevy_inspector::guess_entity_name;
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use bevy_inspector_egui::egui;
+
+use super::{select_all, HierarchyState};
+use crate::inspector::{InspectorSelection, InspectorState};
+
+/// Number of selections remembered by a [`SelectionHistory`]
+pub const HISTORY_LEN: usize = 50;
+
+/// Previous selections of a hierarchy, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct SelectionHistory {
+    entries: VecDeque<Vec<Entity>>,
+    /// index of the entry matching the current selection
+    current: usize,
+}
+
+impl SelectionHistory {
+    /// Remembers `selected` if it changed since the last call. Going back and then selecting
+    /// something else drops the forward entries, like in a browser.
+    pub fn record(&mut self, selected: &SelectedEntities) {
+        let current = self.entries.get(self.current).map(Vec::as_slice);
+        if selected.is_empty() || current == Some(selected.as_slice()) {
+            return;
+        }
+
+        self.entries.truncate(self.current + 1);
+        self.entries.push_back(selected.as_slice().to_vec());
+        if self.entries.len() > HISTORY_LEN {
+            self.entries.pop_front();
+        }
+        self.current = self.entries.len() - 1;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.current > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.current + 1 < self.entries.len()
+    }
+
+    /// Moves up to `offset` entries through the history and returns the selection there
+    pub fn go(&mut self, offset: isize) -> Option<&[Entity]> {
+        let last = self.entries.len().checked_sub(1)?;
+        self.current = self.current.saturating_add_signed(offset).min(last);
+        self.entries.get(self.current).map(Vec::as_slice)
+    }
+
+    /// Previous selections, the most recent first, with their offset for [`SelectionHistory::go`]
+    pub fn recent(&self) -> impl Iterator<Item = (isize, &[Entity])> {
+        self.entries
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, entry)| (i as isize - self.current as isize, entry.as_slice()))
+    }
+
+    /// Removes despawned entities, dropping selections that end up empty or equal to their neighbour
+    pub fn prune(&mut self, entities: &Entities) {
+        let mut kept: VecDeque<Vec<Entity>> = VecDeque::with_capacity(self.entries.len());
+        let mut current = 0;
+        for (i, mut entry) in self.entries.drain(..).enumerate() {
+            entry.retain(|&entity| entities.contains(entity));
+            if !entry.is_empty() && kept.back() != Some(&entry) {
+                kept.push_back(entry);
+            }
+            // the current entry, or the closest one before it that is left
+            if i == self.current {
+                current = kept.len().saturating_sub(1);
+            }
+        }
+        self.entries = kept;
+        self.current = current;
+    }
+}
+
+/// Back and forward buttons, and a menu to jump to a recent selection.
+///
+/// Returns whether the selection changed.
+pub(crate) fn history_ui(ui: &mut egui::Ui, world: &World, state: &mut HierarchyState) -> bool {
+    let mut offset = None;
+    ui.horizontal(|ui| {
+        let back = ui
+            .add_enabled(state.history.can_go_back(), egui::Button::new("⏴"))
+            .on_hover_text("Previous selection");
+        if back.clicked() {
+            offset = Some(-1);
+        }
+        let forward = ui
+            .add_enabled(state.history.can_go_forward(), egui::Button::new("⏵"))
+            .on_hover_text("Next selection");
+        if forward.clicked() {
+            offset = Some(1);
+        }
+
+        ui.menu_button("🕘", |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (entry_offset, entities) in state.history.recent() {
+                        let text = selection_label(world, entities);
+                        if ui.selectable_label(entry_offset == 0, text).clicked() {
+                            offset = Some(entry_offset);
+                            ui.close_menu();
+                        }
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Recent selections");
+    });
+
+    let Some(offset) = offset else {
+        return false;
+    };
+    go(state, offset)
+}
+
+fn selection_label(world: &World, entities: &[Entity]) -> String {
+    let Some(&first) = entities.first() else {
+        return String::new();
+    };
+    let name = guess_entity_name(world, first);
+    match entities.len() {
+        1 => name,
+        len => format!("{name} and {} more", len - 1),
+    }
+}
+
+fn go(state: &mut HierarchyState, offset: isize) -> bool {
+    // a selection made since the last frame shouldn't be skipped over
+    state.history.record(&state.selected);
+    let Some(entities) = state.history.go(offset) else {
+        return false;
+    };
+    let entities = entities.to_vec();
+    select_all(&mut state.selected, &entities);
+    true
+}
+
+pub(crate) fn record_selection(
+    mut states: Query<&mut HierarchyState>,
+    mut default: ResMut<DefaultLink<HierarchyState>>,
+) {
+    // nothing reacts to history changes, so they don't need change detection
+    for mut state in &mut states {
+        let state = state.bypass_change_detection();
+        state.history.record(&state.selected);
+    }
+    if let DefaultLink::Data(state) = default.bypass_change_detection() {
+        state.history.record(&state.selected);
+    }
+}
+
+/// Steps through the history of the focused hierarchy on [`EditorEvent::SelectionBack`]
+/// and [`EditorEvent::SelectionForward`]
+pub(crate) fn navigate_history(world: &mut World, mut events: Local<EventCursor<EditorEvent>>) {
+    let offset: isize = events
+        .read(world.resource::<Events<EditorEvent>>())
+        .map(|event| match event {
+            EditorEvent::SelectionBack => -1,
+            EditorEvent::SelectionForward => 1,
+            _ => 0,
+        })
+        .sum();
+    if offset == 0 {
+        return;
+    }
+
+    let window = world.resource::<Editor>().focused_window();
+    let navigated = linked_state_mut::<HierarchyState>(world, window)
+        .is_some_and(|mut state| go(&mut state, offset));
+    if navigated {
+        show_entities_in_inspector(world);
+    }
+}
+
+pub(crate) fn show_entities_in_inspector(world: &mut World) {
+    if let Some(mut inspector) = linked_state_mut::<InspectorState>(world, None) {
+        inspector.selected = InspectorSelection::Entities;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(entities: &[Entity]) -> SelectedEntities {
+        let mut selected = SelectedEntities::default();
+        for &entity in entities {
+            selected.select_maybe_add(entity, true);
+        }
+        selected
+    }
+
+    fn recorded(entities: &[&[Entity]]) -> SelectionHistory {
+        let mut history = SelectionHistory::default();
+        for entities in entities {
+            history.record(&selection(entities));
+        }
+        history
+    }
+
+    #[test]
+    fn record_skips_empty_and_unchanged_selections() {
+        let [a, b] = [Entity::from_raw(1), Entity::from_raw(2)];
+        let history = recorded(&[&[a], &[a], &[], &[a, b]]);
+
+        let entries: Vec<_> = history.recent().collect();
+        assert_eq!(entries, [(0, &[a, b][..]), (-1, &[a][..])]);
+    }
+
+    #[test]
+    fn record_is_limited_to_history_len() {
+        let mut history = SelectionHistory::default();
+        for i in 0..HISTORY_LEN as u32 + 10 {
+            history.record(&selection(&[Entity::from_raw(i)]));
+        }
+
+        assert_eq!(history.recent().count(), HISTORY_LEN);
+        assert_eq!(
+            history.go(-(HISTORY_LEN as isize)),
+            Some(&[Entity::from_raw(10)][..])
+        );
+        assert!(!history.can_go_back());
+    }
+
+    #[test]
+    fn go_clamps_to_both_ends() {
+        let [a, b, c] = [
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        ];
+        let mut history = recorded(&[&[a], &[b], &[c]]);
+        assert!(history.can_go_back());
+        assert!(!history.can_go_forward());
+
+        assert_eq!(history.go(-1), Some(&[b][..]));
+        assert_eq!(history.go(-5), Some(&[a][..]));
+        assert!(!history.can_go_back());
+        assert_eq!(history.go(5), Some(&[c][..]));
+        assert!(!history.can_go_forward());
+
+        assert_eq!(SelectionHistory::default().go(-1), None);
+    }
+
+    #[test]
+    fn record_after_going_back_drops_forward_entries() {
+        let [a, b, c, d] = [1, 2, 3, 4].map(Entity::from_raw);
+        let mut history = recorded(&[&[a], &[b], &[c]]);
+        history.go(-2);
+        history.record(&selection(&[d]));
+
+        let entries: Vec<_> = history.recent().collect();
+        assert_eq!(entries, [(0, &[d][..]), (-1, &[a][..])]);
+        assert!(!history.can_go_forward());
+    }
+
+    #[test]
+    fn prune_removes_despawned_entities_and_merges_entries() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|_| world.spawn_empty().id());
+        let mut history = recorded(&[&[a], &[b], &[a, c], &[a], &[c]]);
+        history.go(-1);
+
+        world.despawn(c);
+        history.prune(world.entities());
+
+        // `[a, c]` becomes `[a]`, merging with its neighbours, and `[c]` is gone
+        let entries: Vec<_> = history.recent().collect();
+        assert_eq!(entries, [(0, &[a][..]), (-1, &[b][..]), (-2, &[a][..])]);
+    }
+
+    #[test]
+    fn prune_keeps_the_closest_earlier_entry_current() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|_| world.spawn_empty().id());
+        let mut history = recorded(&[&[a], &[b], &[c]]);
+        history.go(-1);
+
+        world.despawn(b);
+        history.prune(world.entities());
+
+        assert_eq!(history.go(0), Some(&[a][..]));
+        assert!(history.can_go_forward());
+    }
+}
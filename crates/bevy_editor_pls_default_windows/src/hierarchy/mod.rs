@@ -1,114 +1,251 @@
-// pub mod picking;
+pub mod clipboard;
+mod drag_drop;
+pub mod duplicate;
+pub mod filter;
+pub mod highlight;
+pub mod history;
+pub mod picking;
+pub mod query_view;
+mod toggles;
 
 use bevy::ecs::entity::Entities;
+use bevy::ecs::event::EventCursor;
 use bevy::pbr::wireframe::Wireframe;
 use bevy::prelude::*;
 use bevy::reflect::TypeRegistry;
 use bevy::render::sync_world::RenderEntity;
+use bevy::render::view::RenderLayers;
 use bevy::render::{Extract, RenderApp};
-use bevy_editor_pls_core::editor_window::{DefaultLink, Link};
+use bevy_editor_pls_core::editor_window::{
+    linked_state_mut, DefaultLink, EditorWindowInstance, EditorWindowsCollection, Link,
+};
 use bevy_editor_pls_core::{editor, AddEditorWindow};
+use bevy_inspector_egui::bevy_egui::EguiClipboard;
 use bevy_inspector_egui::bevy_inspector::guess_entity_name;
 use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use bevy_inspector_egui::egui::collapsing_header::CollapsingState;
 use bevy_inspector_egui::egui::text::CCursorRange;
 use bevy_inspector_egui::egui::{self, ScrollArea};
 
 use bevy_editor_pls_core::{
     editor_window::{EditorWindow, EditorWindowContext},
-    Editor,
+    Editor, EditorEvent,
 };
-// use bevy_mod_picking::backends::egui::EguiPointer;
-// use bevy_mod_picking::prelude::{IsPointerEvent, PointerClick, PointerButton};
 
-// use crate::add::{add_ui, AddWindow, AddWindowState};
-use crate::debug_settings::{DebugSettings, DebugSettingsWindow};
+use crate::add::{self, add_ui, AddWindowState};
+use crate::bookmarks;
+use crate::cameras::{is_editor_only, EditorCamera};
+use crate::debug_settings::{DebugSettings, DebugSettingsWindow, HighlightMode};
+use crate::entity_field::EntityPicker;
 use crate::inspector::{InspectorSelection, InspectorState, InspectorWindow};
+use filter::{FilterMode, HierarchyFilter, InternalEntities};
+use history::SelectionHistory;
+
+pub use drag_drop::DraggedEntities;
 
 #[derive(Component)]
 pub struct HideInEditor;
 
+/// Entities belonging to the editor: editor windows and cameras, gizmo markers, and anything
+/// with [`HideInEditor`], including their descendants
+pub fn is_editor_internal(world: &World, entity: Entity) -> bool {
+    let internal = |entity: Entity| {
+        let entity = world.entity(entity);
+        entity.contains::<HideInEditor>()
+            || entity.contains::<EditorWindowsCollection>()
+            || entity.contains::<EditorWindowInstance>()
+            || entity.contains::<EditorCamera>()
+            || is_editor_only(entity.get::<RenderLayers>())
+    };
+    std::iter::successors(Some(entity), |&entity| {
+        world.get::<Parent>(entity).map(Parent::get)
+    })
+    .any(internal)
+}
+
+/// Whether viewport picking skips `entity`, because it or one of its ancestors is hidden,
+/// unpickable or [`EditorLocked`]
+pub fn is_unpickable(world: &World, entity: Entity) -> bool {
+    std::iter::successors(Some(entity), |&entity| {
+        world.get::<Parent>(entity).map(Parent::get)
+    })
+    .any(|entity| {
+        let Ok(entity) = world.get_entity(entity) else {
+            return false;
+        };
+        entity.contains::<picking::NoEditorPicking>()
+            || entity.contains::<HideInEditor>()
+            || entity.contains::<EditorLocked>()
+    })
+}
+
+/// Whether `entity` or one of its ancestors is [`EditorLocked`]
+pub fn is_locked(world: &World, entity: Entity) -> bool {
+    std::iter::successors(Some(entity), |&entity| {
+        world.get::<Parent>(entity).map(Parent::get)
+    })
+    .any(|entity| world.get::<EditorLocked>(entity).is_some())
+}
+
+/// Keeps the entity and its descendants from being picked in the viewport, moved by the gizmo,
+/// despawned, cut, reparented and edited in the inspector
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EditorLocked;
+
 #[derive(Debug, Copy, Clone, Component, Default)]
 pub struct HierarchyWindow;
 impl EditorWindow for HierarchyWindow {
     fn ui(&self, world: &mut World, mut cx: EditorWindowContext, ui: &mut egui::Ui) {
-        let mut hierarchy_state = cx.get::<HierarchyState>(world).unwrap().clone();
-
-        ScrollArea::vertical().show(ui, |ui| {
-            let type_registry = world.resource::<AppTypeRegistry>().clone();
-            let type_registry = type_registry.read();
-            let new_selected = Hierarchy {
-                world,
-                state: &mut hierarchy_state,
-                type_registry: &type_registry,
-                // add_state: add_state.as_deref(),
-            }
-            .show(ui);
+        hierarchy_window_ui(world, &mut cx, ui, None);
+    }
+}
 
-            if new_selected {
-                let mut v = cx.get_mut::<InspectorState>(world).unwrap();
-                v.selected = InspectorSelection::Entities;
-            }
-            let mut v = cx.get_mut::<HierarchyState>(world).unwrap();
-            *v.as_mut() = hierarchy_state;
-        });
+/// The hierarchy of `cx`, listing the entities of `query` instead of its own filter if given
+fn hierarchy_window_ui(
+    world: &mut World,
+    cx: &mut EditorWindowContext,
+    ui: &mut egui::Ui,
+    mut query: Option<&mut HierarchyFilter>,
+) {
+    let mut hierarchy_state = cx.get::<HierarchyState>(world).unwrap().clone();
+    let focused = world.resource::<Editor>().focused_window() == Some(cx.entity);
+
+    let (navigated, added) = ui
+        .horizontal(|ui| {
+            let added = add::add_entity_button(ui, world);
+            (history::history_ui(ui, world, &mut hierarchy_state), added)
+        })
+        .inner;
+    if let Some(entity) = added {
+        hierarchy_state.selected.select_replace(entity);
+    }
+    if let Some(query) = &mut query {
+        std::mem::swap(&mut hierarchy_state.filter, *query);
     }
+    {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        hierarchy_state.filter.ui(ui, &type_registry);
+    }
+    skipped_components_ui(ui, &mut hierarchy_state.skipped);
+    ui.separator();
+
+    // dragging rows reparents entities instead
+    ScrollArea::vertical().drag_to_scroll(false).show(ui, |ui| {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let new_selected = Hierarchy {
+            world,
+            state: &mut hierarchy_state,
+            type_registry: &type_registry,
+            focused,
+        }
+        .show(ui);
+
+        if new_selected || navigated || added.is_some() {
+            let mut v = cx.get_mut::<InspectorState>(world).unwrap();
+            v.selected = InspectorSelection::Entities;
+        }
+        if let Some(query) = query {
+            std::mem::swap(&mut hierarchy_state.filter, query);
+        }
+        let mut v = cx.get_mut::<HierarchyState>(world).unwrap();
+        *v.as_mut() = hierarchy_state;
+    });
+}
+
+fn skipped_components_ui(ui: &mut egui::Ui, skipped: &mut Vec<String>) {
+    if skipped.is_empty() {
+        return;
+    }
+    ui.horizontal_wrapped(|ui| {
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!("Not duplicated, not reflected: {}", skipped.join(", ")),
+        );
+        if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+            skipped.clear();
+        }
+    });
 }
 
 impl Plugin for HierarchyWindow {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_editor_window::<HierarchyWindow>();
+        app.add_editor_window::<query_view::QueryHierarchyWindow>();
         app.register_type::<Link<HierarchyState>>();
+        app.register_type::<EditorLocked>();
+        app.register_type::<toggles::EditorHidden>();
         app.init_resource::<DefaultLink<HierarchyState>>(); 
 
-        // picking::setup(app);
-        app.add_systems(PostUpdate, clear_removed_entites);
-        // .add_system(handle_events);
+        highlight::setup(app);
+        app.init_resource::<filter::NameOrParentChanges>();
+        app.add_systems(
+            PostUpdate,
+            (
+                (clear_removed_entites, history::record_selection).chain(),
+                filter::track_name_or_parent_changes.before(bevy_editor_pls_core::EditorSet::UI),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                duplicate_selected,
+                toggles::sync_editor_hidden,
+                history::navigate_history,
+            ),
+        );
 
         app.sub_app_mut(RenderApp)
             .add_systems(ExtractSchedule, extract_wireframe_for_selected);
     }
 }
 
-fn clear_removed_entites(mut state: Query<&mut HierarchyState>, entities: &Entities) {
+fn clear_removed_entites(
+    mut state: Query<&mut HierarchyState>,
+    mut default: ResMut<DefaultLink<HierarchyState>>,
+    entities: &Entities,
+) {
     for mut state in state.iter_mut() {
         state.selected.retain(|entity| entities.contains(entity));
+        state.history.prune(entities);
+    }
+    if let DefaultLink::Data(state) = default.as_mut() {
+        state.selected.retain(|entity| entities.contains(entity));
+        state.history.prune(entities);
     }
 }
 
-/*fn handle_events(
-    mut click_events: EventReader<PointerClick>,
-    mut editor: ResMut<Editor>,
-    editor_state: Res<EditorState>,
-    input: Res<Input<KeyCode>>,
-    egui_entity: Query<&EguiPointer>,
-    mut egui_ctx: ResMut<EguiContext>,
-) {
-    for click in click_events.iter() {
-        if !editor_state.active {
-            return;
-        }
-
-        if click.event_data().button != PointerButton::Primary {
-            continue;
-        }
-
-        if egui_entity.get(click.target()).is_ok() || egui_ctx.ctx_mut().wants_pointer_input() {
-            continue;
-        };
-
-        let state = editor.window_state_mut::<HierarchyWindow>().unwrap();
+/// Duplicates the selection of the focused hierarchy on [`EditorEvent::DuplicateSelected`]
+fn duplicate_selected(world: &mut World, mut events: Local<EventCursor<EditorEvent>>) {
+    let requested = events
+        .read(world.resource::<Events<EditorEvent>>())
+        .filter(|event| matches!(event, EditorEvent::DuplicateSelected))
+        .count()
+        > 0;
+    if !requested {
+        return;
+    }
 
-        let ctrl = input.any_pressed([KeyCode::LControl, KeyCode::RControl]);
-        let shift = input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
-        let mode = SelectionMode::from_ctrl_shift(ctrl, shift);
+    let window = world.resource::<Editor>().focused_window();
+    let Some(selected) =
+        linked_state_mut::<HierarchyState>(world, window).map(|s| s.selected.as_slice().to_vec())
+    else {
+        return;
+    };
+    let duplicated = duplicate::duplicate_entities(world, &selected);
+    if let Some(mut state) = linked_state_mut::<HierarchyState>(world, window) {
+        select_all(&mut state.selected, &duplicated.entities);
+        state.skipped = duplicated.skipped;
+    }
+}
 
-        let entity = click.target();
-        info!("Selecting mesh, found {:?}", entity);
-        state
-            .selected
-            .select(mode, entity, |_, _| std::iter::once(entity));
+fn select_all(selected: &mut SelectedEntities, entities: &[Entity]) {
+    selected.clear();
+    for &entity in entities {
+        selected.select_maybe_add(entity, true);
     }
-}*/
+}
 
 fn extract_wireframe_for_selected(
     debug: Extract<Res<DebugSettings>>,
@@ -116,7 +253,7 @@ fn extract_wireframe_for_selected(
     mut commands: Commands,
     query: Extract<Query<RenderEntity>>,
 ) {
-    if debug.highlight_selected {
+    if debug.highlight_selected && debug.highlight_mode == HighlightMode::Wireframe {
         for state in state.iter() {
             let selected = &state.selected;
             for selected in selected.iter() {
@@ -133,7 +270,26 @@ fn extract_wireframe_for_selected(
 #[derive(Default, Clone, Component, TypePath)]
 pub struct HierarchyState {
     pub selected: SelectedEntities,
+    pub filter: HierarchyFilter,
+    pub history: SelectionHistory,
     rename_info: Option<RenameInfo>,
+    reveal: Option<Reveal>,
+    /// components left out of the last duplicate, shown until dismissed
+    skipped: Vec<String>,
+}
+
+impl HierarchyState {
+    /// Expands the ancestors of `entity` and scrolls to it
+    pub fn reveal(&mut self, entity: Entity) {
+        self.reveal = Some(Reveal { entity, frames: 0 });
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Reveal {
+    entity: Entity,
+    /// frames waited for the row to show up, the ancestors are only expanded the frame after
+    frames: u8,
 }
 
 #[derive(Debug, Clone)]
@@ -147,85 +303,444 @@ struct Hierarchy<'a> {
     world: &'a mut World,
     state: &'a mut HierarchyState,
     type_registry: &'a TypeRegistry,
-    // add_state: Option<&'a AddWindowState>,
+    /// whether keyboard shortcuts are meant for this hierarchy
+    focused: bool,
 }
 
 impl Hierarchy<'_> {
     fn show(&mut self, ui: &mut egui::Ui) -> bool {
-        let mut despawn_recursive = None;
-        let mut despawn = None;
+        let mut actions = EntityActions::default();
+        let mut rows = Vec::new();
+
+        let mode = self.state.filter.mode;
+        let internal_entities = self.state.filter.internal;
+        let filter_result = match self.state.filter.is_active() {
+            true => Some(self.state.filter.apply(self.world)),
+            false => None,
+        };
+
+        // while an `Entity` field waits for a pick, clicking a row picks it instead of selecting it
+        let picking = self
+            .world
+            .get_resource::<EntityPicker>()
+            .is_some_and(EntityPicker::is_picking);
+        let selected_before = picking.then(|| self.state.selected.clone());
+        let reveal_ancestors: Vec<Entity> = match self.state.reveal {
+            Some(reveal) => {
+                std::iter::successors(self.world.get::<Parent>(reveal.entity), |parent| {
+                    self.world.get::<Parent>(parent.get())
+                })
+                .map(Parent::get)
+                .collect()
+            }
+            None => Vec::new(),
+        };
 
         let HierarchyState {
             selected,
             rename_info,
+            filter: _,
+            history: _,
+            reveal: _,
+            skipped: _,
         } = self.state;
 
-        let new_selection = bevy_inspector_egui::bevy_inspector::hierarchy::Hierarchy {
-            extra_state: rename_info,
-            world: self.world,
-            type_registry: self.type_registry,
-            selected,
-            context_menu: Some(&mut |ui, entity, world, rename_info| {
-                if ui.button("Despawn").clicked() {
-                    despawn_recursive = Some(entity);
+        let mut new_selection = match filter_result {
+            Some(filter_result) if mode != FilterMode::Tree => {
+                let mut entities: Vec<Entity> = filter_result.matches.iter().copied().collect();
+                if mode == FilterMode::Roots {
+                    // matches inside other matches are listed as their children
+                    let world = &*self.world;
+                    entities.retain(|&entity| {
+                        std::iter::successors(world.get::<Parent>(entity), |parent| {
+                            world.get::<Parent>(parent.get())
+                        })
+                        .all(|parent| !filter_result.matches.contains(&parent.get()))
+                    });
                 }
-
-                if ui.button("Remove keeping children").clicked() {
-                    despawn = Some(entity);
+                entities.sort();
+                EntityList {
+                    world: self.world,
+                    selected,
+                    rename_info,
+                    rows: &mut rows,
+                    context_menu: &mut |ui, entity, world, rename_info| {
+                        entity_context_menu(ui, entity, world, rename_info, &mut actions)
+                    },
+                    children: mode == FilterMode::Roots,
+                    hide_internal: internal_entities == InternalEntities::Hidden,
+                    new_selection: false,
                 }
+                .show(ui, &entities)
+            }
+            _ => bevy_inspector_egui::bevy_inspector::hierarchy::Hierarchy {
+                extra_state: rename_info,
+                world: self.world,
+                type_registry: self.type_registry,
+                selected,
+                context_menu: Some(&mut |ui, entity, world, rename_info| {
+                    entity_context_menu(ui, entity, world, rename_info, &mut actions)
+                }),
+                shortcircuit_entity: Some(&mut |ui, entity, world, rename_info| {
+                    if internal_entities == InternalEntities::Hidden
+                        && is_editor_internal(world, entity)
+                    {
+                        return true;
+                    }
+                    if let Some(filter_result) = filter_result {
+                        if !filter_result.visible.contains(&entity) {
+                            return true;
+                        }
+                        // ancestors of matches are expanded so the matches can be seen
+                        let leads_to_match =
+                            world.get::<Children>(entity).is_some_and(|children| {
+                                children
+                                    .iter()
+                                    .any(|child| filter_result.visible.contains(child))
+                            });
+                        if leads_to_match {
+                            expand_row(ui, entity);
+                        }
+                    }
+                    if reveal_ancestors.contains(&entity) {
+                        expand_row(ui, entity);
+                    }
 
-                if ui.button("Rename").clicked() {
-                    let entity_name = guess_entity_name(world, entity);
-                    *rename_info = Some(RenameInfo {
-                        entity,
-                        renaming: true,
-                        current_rename: entity_name,
-                    });
-                    ui.close_menu();
-                }
+                    rows.push(drag_drop::Row::new(ui, entity));
 
-                // if let Some(add_state) = self.add_state {
-                //     ui.menu_button("Add", |ui| {
-                //         if let Some(add_item) = add_ui(ui, add_state) {
-                //             add_item.add_to_entity(world, entity);
-                //             ui.close_menu();
-                //         }
-                //     });
-                // }
-            }),
-            shortcircuit_entity: Some(&mut |ui, entity, world, rename_info| {
-                if let Some(rename_info) = rename_info {
-                    if rename_info.renaming && rename_info.entity == entity {
-                        rename_entity_ui(ui, rename_info, world);
+                    if let Some(rename_info) = rename_info {
+                        if rename_info.renaming && rename_info.entity == entity {
+                            rename_entity_ui(ui, rename_info, world);
 
-                        return true;
+                            return true;
+                        }
                     }
+
+                    false
+                }),
+            }
+            .show::<()>(ui),
+        };
+
+        let rects = drag_drop::row_rects(ui, &rows);
+        if let Some(mut reveal) = self.state.reveal.take() {
+            match rects.iter().find(|&&(entity, _)| entity == reveal.entity) {
+                Some(&(_, rect)) => ui.scroll_to_rect(rect, Some(egui::Align::Center)),
+                None if reveal.frames < 2 => {
+                    reveal.frames += 1;
+                    self.state.reveal = Some(reveal);
                 }
+                None => {}
+            }
+        }
+        if let Some(selected_before) = selected_before {
+            let clicked = ui.input(|i| {
+                i.pointer
+                    .primary_clicked()
+                    .then(|| i.pointer.interact_pos())
+                    .flatten()
+            });
+            let row = clicked.and_then(|pos| rects.iter().find(|(_, rect)| rect.contains(pos)));
+            if let Some(&(entity, _)) = row {
+                self.world.resource_mut::<EntityPicker>().pick(entity);
+                self.state.selected = selected_before;
+                new_selection = false;
+            }
+        }
+        if internal_entities == InternalEntities::Dimmed {
+            let fill = ui.visuals().panel_fill.gamma_multiply(0.6);
+            for &(entity, rect) in &rects {
+                if is_editor_internal(self.world, entity) {
+                    ui.painter().rect_filled(rect, 0.0, fill);
+                }
+            }
+        }
+        toggles::toggles_ui(ui, self.world, &rects);
+        drag_drop::drag_drop_ui(ui, self.world, &self.state.selected, &rects);
 
-                false
-            }),
+        if let Some(entity) = actions.despawn_recursive {
+            if !is_locked(self.world, entity) {
+                bevy::hierarchy::despawn_with_children_recursive(self.world, entity, true);
+            }
+        }
+        if let Some(entity) = actions.despawn {
+            if !is_locked(self.world, entity) {
+                self.world.entity_mut(entity).despawn();
+                self.state.selected.remove(entity);
+            }
+        }
+        if let Some(entity) = actions.duplicate {
+            let entities = selection_or(&self.state.selected, entity);
+            let duplicated = duplicate::duplicate_entities(self.world, &entities);
+            select_all(&mut self.state.selected, &duplicated.entities);
+            self.state.skipped = duplicated.skipped;
+            new_selection = true;
         }
-        .show::<Without<HideInEditor>>(ui);
 
-        if let Some(entity) = despawn_recursive {
-            bevy::hierarchy::despawn_with_children_recursive(self.world, entity, true);
+        let mut paste = None;
+        if self.focused && !ui.ctx().wants_keyboard_input() {
+            let first_selected = self.state.selected.as_slice().first().copied();
+            ui.input(|input| {
+                for event in &input.events {
+                    match event {
+                        egui::Event::Copy => actions.copy = actions.copy.or(first_selected),
+                        egui::Event::Cut => actions.cut = actions.cut.or(first_selected),
+                        egui::Event::Paste(text) => paste = Some((first_selected, text.clone())),
+                        _ => {}
+                    }
+                }
+            });
         }
-        if let Some(entity) = despawn {
-            self.world.entity_mut(entity).despawn();
-            self.state.selected.remove(entity);
+        if let Some(parent) = actions.paste_into {
+            let text = self
+                .world
+                .get_resource_mut::<EguiClipboard>()
+                .and_then(|mut clipboard| clipboard.get_contents());
+            paste = text.map(|text| (Some(parent), text));
         }
 
-        if ui.input(|input| input.key_pressed(egui::Key::Delete)) {
-            for entity in self.state.selected.iter() {
-                self.world.entity_mut(entity).despawn_recursive();
+        for (entity, cut) in [(actions.copy, false), (actions.cut, true)] {
+            let Some(entity) = entity else {
+                continue;
+            };
+            let entities = selection_or(&self.state.selected, entity);
+            match clipboard::copy_entities(self.world, &entities) {
+                Ok(ron) => ui.ctx().copy_text(ron),
+                Err(err) => {
+                    error!("Failed to copy entities: {}", err);
+                    continue;
+                }
+            }
+            if cut {
+                // locked entities are only copied
+                for &entity in &entities {
+                    if self.world.get_entity(entity).is_ok() && !is_locked(self.world, entity) {
+                        bevy::hierarchy::despawn_with_children_recursive(self.world, entity, false);
+                    }
+                }
+                let world = &*self.world;
+                self.state
+                    .selected
+                    .retain(|entity| world.get_entity(entity).is_ok());
             }
-            self.state.selected.clear();
+        }
+
+        if let Some((parent, text)) = paste {
+            match clipboard::paste_entities(self.world, &text, parent) {
+                Ok(pasted) => {
+                    select_all(&mut self.state.selected, &pasted);
+                    new_selection = true;
+                }
+                Err(err) => warn!("Clipboard doesn't contain pasteable entities: {}", err),
+            }
+        }
+
+        if self.focused
+            && !ui.ctx().wants_keyboard_input()
+            && ui.input(|input| input.key_pressed(egui::Key::Delete))
+        {
+            let (locked, unlocked): (Vec<_>, Vec<_>) = self
+                .state
+                .selected
+                .iter()
+                .partition(|&entity| is_locked(self.world, entity));
+            for entity in unlocked {
+                // might already be gone with a selected ancestor
+                if let Ok(entity) = self.world.get_entity_mut(entity) {
+                    entity.despawn_recursive();
+                }
+            }
+            self.state
+                .selected
+                .retain(|entity| locked.contains(&entity));
         }
 
         new_selection
     }
 }
 
+/// Opens the row of `entity` in the hierarchy widget
+fn expand_row(ui: &egui::Ui, entity: Entity) {
+    let id = ui.make_persistent_id(egui::Id::new(entity));
+    let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, false);
+    state.set_open(true);
+    state.store(ui.ctx());
+}
+
+/// Selects `entity` in the [`DefaultLink`] hierarchy, expanding and scrolling the hierarchy to it
+pub fn reveal_entity(world: &mut World, entity: Entity) {
+    {
+        let Some(mut state) = linked_state_mut::<HierarchyState>(world, None) else {
+            return;
+        };
+        state.selected.select_replace(entity);
+        state.reveal(entity);
+    }
+    history::show_entities_in_inspector(world);
+}
+
+/// Context menu actions, applied once the hierarchy is done drawing
+#[derive(Default)]
+struct EntityActions {
+    despawn_recursive: Option<Entity>,
+    despawn: Option<Entity>,
+    duplicate: Option<Entity>,
+    copy: Option<Entity>,
+    cut: Option<Entity>,
+    /// paste the clipboard as children of this entity
+    paste_into: Option<Entity>,
+}
+
+/// The whole selection if `entity` is part of it, otherwise just `entity`
+fn selection_or(selected: &SelectedEntities, entity: Entity) -> Vec<Entity> {
+    match selected.contains(entity) {
+        true => selected.as_slice().to_vec(),
+        false => vec![entity],
+    }
+}
+
+fn entity_context_menu(
+    ui: &mut egui::Ui,
+    entity: Entity,
+    world: &mut World,
+    rename_info: &mut Option<RenameInfo>,
+    actions: &mut EntityActions,
+) {
+    if ui.button("Despawn").clicked() {
+        actions.despawn_recursive = Some(entity);
+    }
+
+    if ui.button("Remove keeping children").clicked() {
+        actions.despawn = Some(entity);
+    }
+
+    if ui.button("Duplicate").clicked() {
+        actions.duplicate = Some(entity);
+        ui.close_menu();
+    }
+
+    ui.separator();
+    if ui.button("Copy").clicked() {
+        actions.copy = Some(entity);
+        ui.close_menu();
+    }
+    if ui.button("Cut").clicked() {
+        actions.cut = Some(entity);
+        ui.close_menu();
+    }
+    if ui.button("Paste as child").clicked() {
+        actions.paste_into = Some(entity);
+        ui.close_menu();
+    }
+
+    let bookmark = match bookmarks::is_bookmarked(world, entity) {
+        true => "Remove bookmark",
+        false => "Bookmark",
+    };
+    if ui.button(bookmark).clicked() {
+        bookmarks::toggle_bookmark(world, entity);
+        ui.close_menu();
+    }
+
+    if ui.button("Rename").clicked() {
+        let entity_name = guess_entity_name(world, entity);
+        *rename_info = Some(RenameInfo {
+            entity,
+            renaming: true,
+            current_rename: entity_name,
+        });
+        ui.close_menu();
+    }
+
+    if let Some(add_state) = world.get_resource::<AddWindowState>() {
+        let add_item = ui
+            .menu_button("Add", |ui| add_ui(ui, add_state))
+            .inner
+            .flatten();
+        if let Some(add_item) = add_item {
+            add_item.add_to_entity(world, entity);
+        }
+    }
+}
+
+/// Filter matches listed as their own roots, behaving like the rows of the tree
+struct EntityList<'a> {
+    world: &'a mut World,
+    selected: &'a mut SelectedEntities,
+    rename_info: &'a mut Option<RenameInfo>,
+    rows: &'a mut Vec<drag_drop::Row>,
+    context_menu: &'a mut dyn FnMut(&mut egui::Ui, Entity, &mut World, &mut Option<RenameInfo>),
+    /// whether the children of listed entities can be expanded
+    children: bool,
+    /// leave out editor internal children
+    hide_internal: bool,
+    new_selection: bool,
+}
+
+impl EntityList<'_> {
+    fn show(mut self, ui: &mut egui::Ui, entities: &[Entity]) -> bool {
+        for &entity in entities {
+            self.row_ui(ui, entity, entities);
+        }
+        self.new_selection
+    }
+
+    fn row_ui(&mut self, ui: &mut egui::Ui, entity: Entity, siblings: &[Entity]) {
+        let children: Vec<Entity> = match self.world.get::<Children>(entity) {
+            Some(children) if self.children => children
+                .iter()
+                .copied()
+                .filter(|&child| !(self.hide_internal && is_editor_internal(self.world, child)))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        if children.is_empty() {
+            self.label_ui(ui, entity, siblings);
+            return;
+        }
+
+        let id = ui.make_persistent_id(egui::Id::new(entity));
+        CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| self.label_ui(ui, entity, siblings))
+            .body(|ui| {
+                for &child in &children {
+                    self.row_ui(ui, child, &children);
+                }
+            });
+    }
+
+    fn label_ui(&mut self, ui: &mut egui::Ui, entity: Entity, siblings: &[Entity]) {
+        self.rows.push(drag_drop::Row::new(ui, entity));
+
+        if let Some(rename_info) = self.rename_info {
+            if rename_info.renaming && rename_info.entity == entity {
+                rename_entity_ui(ui, rename_info, self.world);
+                return;
+            }
+        }
+
+        let name = guess_entity_name(self.world, entity);
+        let response = ui.selectable_label(self.selected.contains(entity), name);
+
+        if response.clicked() {
+            let extend_with = |from, to| {
+                let from = siblings.iter().position(|&entity| entity == from);
+                let to = siblings.iter().position(|&entity| entity == to);
+                from.zip(to)
+                    .map(|(from, to)| siblings[from.min(to)..=from.max(to)].iter().copied())
+                    .into_iter()
+                    .flatten()
+            };
+            let selection_mode = ui.input(|input| input.modifiers.into());
+            self.selected.select(selection_mode, entity, extend_with);
+            self.new_selection = true;
+        }
+
+        response.context_menu(|ui| (self.context_menu)(ui, entity, self.world, self.rename_info));
+    }
+}
+
 fn rename_entity_ui(ui: &mut egui::Ui, rename_info: &mut RenameInfo, world: &mut World) {
     use egui::epaint::text::cursor::CCursor;
     use egui::widgets::text_edit::{TextEdit, TextEditOutput};
@@ -1,32 +1,60 @@
-use bevy::{prelude::*, render::render_resource::PrimitiveTopology};
-use bevy_mod_picking::prelude::{PickRaycastTarget, PickableBundle};
+use bevy::ecs::system::SystemState;
+use bevy::picking::focus::HoverMap;
+use bevy::picking::mesh_picking::ray_cast::{MeshRayCast, RayCastSettings, RayCastVisibility};
+use bevy::picking::pointer::PointerId;
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
 
-pub struct EditorPickingSet;
+use super::is_unpickable;
+use crate::cameras::is_editor_only;
 
 /// Prevents the entity from being selectable in the editor window.
 #[derive(Component)]
 pub struct NoEditorPicking;
 
-pub fn setup(app: &mut App) {
-    app.add_plugins(bevy_mod_picking::plugins::DefaultPickingPlugins)
-        .add_system(auto_add_editor_picking_set);
-}
+/// The closest entity at `position` in the viewport of `camera_entity`, in window logical pixels.
+///
+/// Meshes are ray cast from the camera, so the game doesn't need any picking plugins. Sprites and
+/// UI nodes are found in the [`HoverMap`] of the picking backends the game added itself.
+pub(crate) fn entity_at(
+    world: &mut World,
+    camera_entity: Entity,
+    position: Vec2,
+) -> Option<Entity> {
+    let mut state: SystemState<(Query<(&Camera, &GlobalTransform)>, MeshRayCast)> =
+        SystemState::new(world);
+    let (cameras, mut ray_cast) = state.get(world);
+
+    let (camera, camera_transform) = cameras.get(camera_entity).ok()?;
+    let viewport = camera.logical_viewport_rect()?;
+    let pickable = |entity: Entity| {
+        !is_unpickable(world, entity) && !is_editor_only(world.get::<RenderLayers>(entity))
+    };
+
+    let mesh_hit = camera
+        .viewport_to_world(camera_transform, position - viewport.min)
+        .ok()
+        .and_then(|ray| {
+            let settings = RayCastSettings::default()
+                .with_visibility(RayCastVisibility::VisibleInView)
+                .with_filter(&pickable);
+            ray_cast
+                .cast_ray(ray, &settings)
+                .first()
+                .map(|(entity, hit)| (*entity, hit.distance))
+        });
+
+    let hovered = world
+        .get_resource::<HoverMap>()
+        .and_then(|hover_map| hover_map.get(&PointerId::Mouse))
+        .into_iter()
+        .flatten()
+        .filter(|(&entity, hit)| hit.camera == camera_entity && pickable(entity))
+        .map(|(&entity, hit)| (entity, hit.depth));
 
-fn auto_add_editor_picking_set(
-    mut commands: Commands,
-    meshes: Res<Assets<Mesh>>,
-    meshes_query: Query<
-        (Entity, &Handle<Mesh>),
-        (Without<PickRaycastTarget>, Without<NoEditorPicking>),
-    >,
-) {
-    for (entity, handle) in meshes_query.iter() {
-        if let Some(mesh) = meshes.get(handle) {
-            if let PrimitiveTopology::TriangleList = mesh.primitive_topology() {
-                commands
-                    .entity(entity)
-                    .insert((PickableBundle::default(), PickRaycastTarget::default()));
-            }
-        }
-    }
+    mesh_hit
+        .into_iter()
+        .chain(hovered)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
 }
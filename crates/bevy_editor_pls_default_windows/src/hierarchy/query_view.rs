@@ -0,0 +1,72 @@
+//! Hierarchy tabs listing the entities of a query, like every `PointLight` in the level.
+//!
+//! Each tab opened from `Open window` gets its own query, the selection is shared with the
+//! hierarchy through [`HierarchyState`].
+
+use bevy::prelude::*;
+use bevy_editor_pls_core::editor_window::{EditorWindow, EditorWindowContext};
+use bevy_inspector_egui::egui;
+
+use super::filter::{FilterMode, HierarchyFilter};
+use super::hierarchy_window_ui;
+
+/// The entities listed by a [`QueryHierarchyWindow`], stored next to it
+#[derive(Component, Clone)]
+pub struct HierarchyQuery {
+    pub filter: HierarchyFilter,
+}
+
+impl Default for HierarchyQuery {
+    fn default() -> Self {
+        HierarchyQuery {
+            filter: HierarchyFilter {
+                mode: FilterMode::Roots,
+                ..default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Component, Default)]
+pub struct QueryHierarchyWindow;
+
+impl EditorWindow for QueryHierarchyWindow {
+    fn name(&self, world: &mut World, cx: EditorWindowContext) -> String {
+        let Some(query) = world.get::<HierarchyQuery>(cx.entity) else {
+            return "Query".to_owned();
+        };
+        let components: Vec<String> = query
+            .filter
+            .components
+            .iter()
+            .map(|component| match component.with {
+                true => component.name.clone(),
+                false => format!("!{}", component.name),
+            })
+            .collect();
+        match components.is_empty() {
+            true => "Query".to_owned(),
+            false => format!("Query: {}", components.join(", ")),
+        }
+    }
+
+    fn menu_name(&self) -> String {
+        "Hierarchy Query".to_owned()
+    }
+
+    fn ui(&self, world: &mut World, mut cx: EditorWindowContext, ui: &mut egui::Ui) {
+        // taken out while the hierarchy is drawn, nothing reacts to changes of the query
+        let mut entity = world.entity_mut(cx.entity);
+        let mut query = entity.entry::<HierarchyQuery>().or_default();
+        let mut filter = std::mem::take(&mut query.bypass_change_detection().filter);
+
+        if filter.components.is_empty() {
+            ui.label("Add components to query with +");
+        }
+        hierarchy_window_ui(world, &mut cx, ui, Some(&mut filter));
+
+        if let Some(mut query) = world.get_mut::<HierarchyQuery>(cx.entity) {
+            query.bypass_change_detection().filter = filter;
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Visibility and lock toggles drawn at the end of each hierarchy row.
+
+use bevy::prelude::*;
+use bevy_editor_pls_core::EditorEvent;
+use bevy_inspector_egui::egui;
+
+use super::EditorLocked;
+
+/// Hidden from the hierarchy's eye toggle. Only applies while the editor is active,
+/// the game sees `previous` otherwise.
+///
+/// Reflected, so duplicates of hidden entities are shown again with the editor closed.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(crate) struct EditorHidden {
+    previous: Visibility,
+}
+
+/// Drawn after the rows, so the buttons are on top of the row headers
+pub(crate) fn toggles_ui(ui: &mut egui::Ui, world: &mut World, rects: &[(Entity, egui::Rect)]) {
+    let size = egui::Vec2::splat(ui.spacing().interact_size.y);
+    let clip_rect = ui.clip_rect();
+
+    for &(entity, rect) in rects {
+        if !clip_rect.intersects(rect) {
+            continue;
+        }
+        let lock_rect =
+            egui::Rect::from_min_size(egui::pos2(rect.right() - size.x, rect.top()), size);
+        let eye_rect = lock_rect.translate(egui::vec2(-size.x, 0.0));
+
+        let locked = world.get::<EditorLocked>(entity).is_some();
+        let (text, hover) = match locked {
+            true => ("🔒", "Unlock"),
+            false => (
+                "🔓",
+                "Lock, prevents selecting it in the viewport and editing it",
+            ),
+        };
+        if toggle_button(ui, lock_rect, text, locked, hover) {
+            let mut entity = world.entity_mut(entity);
+            match locked {
+                true => entity.remove::<EditorLocked>(),
+                false => entity.insert(EditorLocked),
+            };
+        }
+
+        if world.get::<Visibility>(entity).is_none() {
+            continue;
+        }
+        let hidden = world.get::<EditorHidden>(entity).is_some();
+        let hover = match hidden {
+            true => "Show",
+            false => "Hide while in the editor",
+        };
+        if toggle_button(ui, eye_rect, "👁", !hidden, hover) {
+            toggle_hidden(world, entity);
+        }
+    }
+}
+
+fn toggle_button(ui: &mut egui::Ui, rect: egui::Rect, text: &str, on: bool, hover: &str) -> bool {
+    let color = match on {
+        true => ui.visuals().strong_text_color(),
+        false => ui.visuals().weak_text_color(),
+    };
+    let button = egui::Button::new(egui::RichText::new(text).color(color)).frame(false);
+    ui.put(rect, button).on_hover_text(hover).clicked()
+}
+
+fn toggle_hidden(world: &mut World, entity: Entity) {
+    let mut entity = world.entity_mut(entity);
+    match entity.take::<EditorHidden>() {
+        Some(hidden) => {
+            entity.insert(hidden.previous);
+        }
+        None => {
+            let previous = *entity.get::<Visibility>().unwrap();
+            entity.insert((EditorHidden { previous }, Visibility::Hidden));
+        }
+    }
+}
+
+/// Gives the game back the visibility of hidden entities while the editor is closed
+pub(crate) fn sync_editor_hidden(
+    mut editor_events: EventReader<EditorEvent>,
+    mut hidden: Query<(&mut EditorHidden, &mut Visibility)>,
+) {
+    for event in editor_events.read() {
+        let &EditorEvent::Toggle { now_active } = event else {
+            continue;
+        };
+        for (mut hidden, mut visibility) in &mut hidden {
+            if now_active {
+                hidden.previous = *visibility;
+                *visibility = Visibility::Hidden;
+            } else {
+                *visibility = hidden.previous;
+            }
+        }
+    }
+}
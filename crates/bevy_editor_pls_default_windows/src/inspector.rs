@@ -1,19 +1,29 @@
 use std::any::TypeId;
 
-use crate::hierarchy::HierarchyState;
+use crate::add;
+use crate::bookmarks;
+use crate::components::{self, ComponentClipboard};
+use crate::entity_field::{self, EntityPicker};
+use crate::handle_field;
+use crate::hierarchy::{self, HierarchyState};
+use crate::multi_edit;
+use crate::presets::{self, PresetAction, Presets};
+use crate::watches;
 
-// use super::add::{AddWindow, AddWindowState};
 use super::hierarchy::HierarchyWindow;
 use bevy::app::Plugin;
 use bevy::asset::UntypedAssetId;
-use bevy::ecs::component::Component;
+use bevy::ecs::component::{Component, ComponentId};
 use bevy::ecs::entity::Entities;
 use bevy::ecs::reflect;
+use bevy::ecs::world::CommandQueue;
 use bevy::prelude::{AppTypeRegistry, Entity, World};
 use bevy::reflect::{Reflect, TypePath, TypeRegistry};
 use bevy_editor_pls_core::editor_window::{DefaultLink, EditorWindow, EditorWindowContext, Link};
 use bevy_editor_pls_core::AddEditorWindow;
 use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use bevy_inspector_egui::reflect_inspector::{Context, InspectorUi};
+use bevy_inspector_egui::restricted_world_view::RestrictedWorldView;
 use bevy_inspector_egui::{bevy_inspector, egui};
 
 // TODO cant make reflect because of UnTypedAssetId
@@ -48,15 +58,7 @@ impl EditorWindow for InspectorWindow {
         let selected = &cx.get::<InspectorState>(world).unwrap().selected.clone(); // TODO don't clone
         let entities = &cx.get::<HierarchyState>(world).unwrap().selected.clone();
 
-        // let add_window_state = cx.state::<AddWindow>();
-        inspector(
-            world,
-            selected,
-            entities,
-            ui,
-            // add_window_state,
-            &type_registry,
-        );
+        inspector(world, selected, entities, ui, &type_registry);
     }
 }
 
@@ -65,6 +67,16 @@ impl Plugin for InspectorWindow {
         app.add_editor_window::<Self>();
         app.register_type::<Link<InspectorState>>();
         app.init_resource::<DefaultLink<InspectorState>>(); 
+        app.init_resource::<ComponentClipboard>();
+        app.init_resource::<EntityPicker>();
+        handle_field::setup(app);
+    }
+
+    fn finish(&self, app: &mut bevy::prelude::App) {
+        // after `DefaultInspectorConfigPlugin` registered its own ui for `Entity`
+        entity_field::register_entity_ui(app.world_mut());
+        // after the asset types and the default ui of handles are registered
+        handle_field::register_handle_pickers(app.world_mut());
     }
 }
 
@@ -73,53 +85,411 @@ fn inspector(
     selected: &InspectorSelection,
     selected_entities: &SelectedEntities,
     ui: &mut egui::Ui,
-    // add_window_state: Option<&AddWindowState>,
     type_registry: &TypeRegistry,
 ) {
     egui::ScrollArea::vertical().show(ui, |ui| match *selected {
-        InspectorSelection::Entities => match selected_entities.as_slice() {
-            [] => {
-                ui.label("No entity selected");
-            }
-            &[entity] => {
-                bevy_inspector::ui_for_entity(world, entity, ui);
-                // add_ui(ui, &[entity], world, add_window_state);
+        InspectorSelection::Entities => {
+            let locked = selected_entities
+                .iter()
+                .any(|entity| hierarchy::is_locked(world, entity));
+            if locked {
+                ui.label("🔒 Locked, unlock in the hierarchy to edit");
             }
-            entities => {
-                bevy_inspector::ui_for_entities_shared_components(world, entities, ui);
-                // add_ui(ui, entities, world, add_window_state);
+
+            match selected_entities.as_slice() {
+                [] => {
+                    ui.label("No entity selected");
+                }
+                &[entity] => {
+                    bookmark_button(world, entity, ui);
+                    let headers = ui_for_entity(world, entity, locked, ui, type_registry);
+                    component_menus(world, &[entity], locked, headers, ui);
+                    ui.add_enabled_ui(!locked, |ui| add_ui(ui, &[entity], world));
+                }
+                entities => {
+                    let headers = match relative_toggle(ui) {
+                        true => {
+                            ui.add_enabled_ui(!locked, |ui| {
+                                multi_edit::ui_for_entities_relative(world, entities, ui)
+                            })
+                            .inner
+                        }
+                        false => ui_for_entities(world, entities, locked, ui, type_registry),
+                    };
+                    component_menus(world, entities, locked, headers, ui);
+                    ui.add_enabled_ui(!locked, |ui| add_ui(ui, entities, world));
+                }
             }
-        },
+        }
         InspectorSelection::Resource(type_id, ref name) => {
             ui.label(name);
             bevy_inspector::by_type_id::ui_for_resource(world, type_id, ui, name, type_registry)
         }
         InspectorSelection::Asset(type_id, ref name, handle) => {
-            ui.label(name);
+            ui.horizontal(|ui| {
+                ui.label(name);
+                asset_presets_button(world, handle, ui, type_registry);
+            });
             bevy_inspector::by_type_id::ui_for_asset(world, type_id, handle, ui, type_registry);
         }
     });
 }
 
-// fn add_ui(
-//     ui: &mut egui::Ui,
-//     entities: &[Entity],
-//     world: &mut World,
-//     add_window_state: Option<&AddWindowState>,
-// ) {
-//     if let Some(add_window_state) = add_window_state {
-//         let layout = egui::Layout::top_down(egui::Align::Center).with_cross_justify(true);
-//         ui.with_layout(layout, |ui| {
-//             ui.menu_button("+", |ui| {
-//                 if let Some(add_item) = crate::add::add_ui(ui, add_window_state) {
-//                     for entity in entities {
-//                         add_item.add_to_entity(world, *entity);
-//                     }
-//                 }
-//             });
-//         });
-//     }
-// }
+/// Whether numbers of multiple entities are edited relative to each entity's own value
+fn relative_toggle(ui: &mut egui::Ui) -> bool {
+    let id = ui.id().with("relative_editing");
+    let mut relative = ui.data(|d| d.get_temp::<bool>(id)).unwrap_or(false);
+    ui.checkbox(&mut relative, "Relative")
+        .on_hover_text("Drag numbers to offset each entity's own value, or type +1, *2, =0");
+    ui.data_mut(|d| d.insert_temp(id, relative));
+    relative
+}
+
+fn add_ui(ui: &mut egui::Ui, entities: &[Entity], world: &mut World) {
+    let layout = egui::Layout::top_down(egui::Align::Center).with_cross_justify(true);
+    ui.with_layout(layout, |ui| {
+        add::add_to_entities_button(ui, world, entities);
+        components::add_component_button(ui, world, entities);
+    });
+}
+
+/// A component as listed in the inspector
+struct ComponentRow {
+    name: String,
+    component_id: ComponentId,
+    type_id: Option<TypeId>,
+    size: usize,
+}
+
+/// The components shared by `entities`, sorted by name like `bevy_inspector` does
+fn component_rows(world: &World, entities: &[Entity]) -> Vec<ComponentRow> {
+    let mut rows: Vec<ComponentRow> = components::shared_components(world, entities)
+        .into_iter()
+        .filter_map(|component_id| {
+            let info = world.components().get_info(component_id)?;
+            Some(ComponentRow {
+                name: pretty_type_name::pretty_type_name_str(info.name()),
+                component_id,
+                type_id: info.type_id(),
+                size: info.layout().size(),
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    rows
+}
+
+/// Markers and components that can't be shown only get a label, to right click for their menu
+fn component_label(ui: &mut egui::Ui, id: egui::Id, name: &str) -> egui::Response {
+    ui.indent(id, |ui| {
+        ui.add(egui::Label::new(name).sense(egui::Sense::click()))
+    })
+    .inner
+}
+
+/// The components of `entity` like [`bevy_inspector::ui_for_entity`], with their values disabled
+/// while `locked`. Returns the header of each component for [`component_menus`].
+fn ui_for_entity(
+    world: &mut World,
+    entity: Entity,
+    locked: bool,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+) -> Vec<(ComponentId, egui::Response)> {
+    ui.label(bevy_inspector::guess_entity_name(world, entity));
+
+    let rows = component_rows(world, &[entity]);
+    let mut headers = Vec::with_capacity(rows.len());
+    let mut queue = CommandQueue::default();
+    let mut view = RestrictedWorldView::new(world);
+    for row in rows {
+        let id = egui::Id::new(entity).with(row.component_id);
+        let Some(type_id) = row.type_id.filter(|_| row.size > 0) else {
+            headers.push((row.component_id, component_label(ui, id, &row.name)));
+            continue;
+        };
+
+        // the rest of the world stays accessible, e.g. for the names in `Entity` fields
+        let (mut component_view, rest) = view.split_off_component((entity, type_id));
+        let (value, _, set_changed) =
+            match component_view.get_entity_component_reflect(entity, type_id, type_registry) {
+                Ok(value) => value,
+                Err(_) => {
+                    let label = component_label(ui, id, &row.name)
+                        .on_hover_text("Not registered for reflection");
+                    headers.push((row.component_id, label));
+                    continue;
+                }
+            };
+        let mut cx = Context {
+            world: Some(rest),
+            queue: Some(&mut queue),
+        };
+        let mut env = InspectorUi::for_bevy(type_registry, &mut cx);
+
+        watches::begin_component(ui, &[entity], row.component_id, type_id, &*value);
+        let header = egui::CollapsingHeader::new(&row.name)
+            .id_salt(id)
+            .show(ui, |ui| {
+                ui.add_enabled_ui(!locked, |ui| {
+                    let value = value.as_partial_reflect_mut();
+                    if env.ui_for_reflect_with_options(value, ui, id, &()) {
+                        set_changed();
+                    }
+                });
+            });
+        watches::end_component(ui);
+        headers.push((row.component_id, header.header_response));
+    }
+    queue.apply(world);
+    headers
+}
+
+/// The components shared by `entities` like
+/// [`bevy_inspector::ui_for_entities_shared_components`], with their values disabled while
+/// `locked`. Returns the header of each component for [`component_menus`].
+fn ui_for_entities(
+    world: &mut World,
+    entities: &[Entity],
+    locked: bool,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+) -> Vec<(ComponentId, egui::Response)> {
+    let rows = component_rows(world, entities);
+    let mut headers = Vec::with_capacity(rows.len());
+    let mut queue = CommandQueue::default();
+    let (resources, components) = RestrictedWorldView::resources_components(world);
+    let mut cx = Context {
+        world: Some(resources),
+        queue: Some(&mut queue),
+    };
+    let mut env = InspectorUi::for_bevy(type_registry, &mut cx);
+
+    for row in rows {
+        let id = egui::Id::new("shared_components").with(row.component_id);
+        let Some(type_id) = row.type_id.filter(|_| row.size > 0) else {
+            headers.push((row.component_id, component_label(ui, id, &row.name)));
+            continue;
+        };
+
+        let values: Result<Vec<_>, _> = entities
+            .iter()
+            .enumerate()
+            .filter(|&(i, entity)| !entities[..i].contains(entity))
+            .map(|(_, &entity)| {
+                // SAFETY: the entities are distinct and `env` only has access to resources
+                unsafe {
+                    components.get_entity_component_reflect_unchecked(
+                        entity,
+                        type_id,
+                        type_registry,
+                    )
+                }
+            })
+            .collect();
+        let Ok(mut values) = values else {
+            let label =
+                component_label(ui, id, &row.name).on_hover_text("Not registered for reflection");
+            headers.push((row.component_id, label));
+            continue;
+        };
+
+        if let Some((first, _, _)) = values.first() {
+            watches::begin_component(ui, entities, row.component_id, type_id, &**first);
+        }
+        let header = egui::CollapsingHeader::new(&row.name)
+            .id_salt(id)
+            .show(ui, |ui| {
+                ui.add_enabled_ui(!locked, |ui| {
+                    let mut values_reflect: Vec<_> = values
+                        .iter_mut()
+                        .map(|(value, _, _)| value.as_partial_reflect_mut())
+                        .collect();
+                    let changed = env.ui_for_reflect_many_with_options(
+                        type_id,
+                        &row.name,
+                        ui,
+                        id,
+                        &(),
+                        &mut values_reflect,
+                        &|value| value,
+                    );
+                    if changed {
+                        for (_, _, set_changed) in values {
+                            set_changed();
+                        }
+                    }
+                });
+            });
+        watches::end_component(ui);
+        headers.push((row.component_id, header.header_response));
+    }
+    queue.apply(world);
+    headers
+}
+
+/// Right clicking the header of a component opens its menu, to copy, paste, apply presets or remove
+/// the component. Pasting and removing is disabled while one of `entities` is `locked`.
+fn component_menus(
+    world: &mut World,
+    entities: &[Entity],
+    locked: bool,
+    headers: Vec<(ComponentId, egui::Response)>,
+    ui: &mut egui::Ui,
+) {
+    let Some(&first) = entities.first() else {
+        return;
+    };
+    let copied = world
+        .get_resource::<ComponentClipboard>()
+        .and_then(|clipboard| clipboard.copied.as_ref())
+        .map(|copied| (copied.type_id, copied.name.clone()));
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let mut action = None;
+    for (component_id, header) in headers {
+        let Some(info) = world.components().get_info(component_id) else {
+            continue;
+        };
+        let type_path = info
+            .type_id()
+            .and_then(|type_id| type_registry.get(type_id))
+            .map(|registration| registration.type_info().type_path());
+        let menu = ComponentMenu {
+            world: &*world,
+            entities,
+            component_id,
+            type_id: info.type_id(),
+            type_path,
+            copied: copied.as_ref(),
+            locked,
+        };
+        header.context_menu(|ui| action = menu.ui(ui).or(action.take()));
+    }
+
+    match action {
+        Some(ComponentAction::Copy(component_id)) => {
+            if let Some(ron) = components::copy_component(world, first, component_id) {
+                ui.ctx().copy_text(ron);
+            }
+        }
+        Some(ComponentAction::Paste) => components::paste_component(world, entities),
+        Some(ComponentAction::ApplyPreset(type_path, name)) => {
+            presets::apply_preset(world, entities, &type_path, &name);
+        }
+        Some(ComponentAction::SavePreset(component_id, name)) => {
+            presets::save_preset(world, first, component_id, &name);
+        }
+        Some(ComponentAction::Remove(component_id)) => {
+            for &entity in entities {
+                components::remove_component(world, entity, component_id);
+            }
+        }
+        None => {}
+    }
+}
+
+/// The items of the menu of one component in [`component_menus`]
+struct ComponentMenu<'a> {
+    world: &'a World,
+    entities: &'a [Entity],
+    component_id: ComponentId,
+    type_id: Option<TypeId>,
+    type_path: Option<&'a str>,
+    /// type id and name of the component in the [`ComponentClipboard`]
+    copied: Option<&'a (TypeId, String)>,
+    locked: bool,
+}
+
+impl ComponentMenu<'_> {
+    fn ui(&self, ui: &mut egui::Ui) -> Option<ComponentAction> {
+        let mut action = None;
+        if self.entities.len() == 1 && ui.button("Copy component").clicked() {
+            action = Some(ComponentAction::Copy(self.component_id));
+            ui.close_menu();
+        }
+        let paste = ui.add_enabled(
+            !self.locked && self.copied.is_some_and(|(id, _)| Some(*id) == self.type_id),
+            egui::Button::new("Paste values"),
+        );
+        let paste = match self.copied {
+            _ if self.locked => paste.on_disabled_hover_text("Locked"),
+            Some((_, name)) => paste.on_disabled_hover_text(format!("Copied {name}")),
+            None => paste.on_disabled_hover_text("Nothing copied"),
+        };
+        if paste.clicked() {
+            action = Some(ComponentAction::Paste);
+            ui.close_menu();
+        }
+        if let (Some(presets), Some(type_path)) =
+            (self.world.get_resource::<Presets>(), self.type_path)
+        {
+            match presets::presets_menu(ui, presets, type_path, self.entities.len() == 1) {
+                Some(PresetAction::Apply(name)) => {
+                    action = Some(ComponentAction::ApplyPreset(type_path.to_owned(), name));
+                }
+                Some(PresetAction::Save(name)) => {
+                    action = Some(ComponentAction::SavePreset(self.component_id, name));
+                }
+                None => {}
+            }
+        }
+        if ui
+            .add_enabled(!self.locked, egui::Button::new("Remove component"))
+            .clicked()
+        {
+            action = Some(ComponentAction::Remove(self.component_id));
+            ui.close_menu();
+        }
+        action
+    }
+}
+
+enum ComponentAction {
+    Copy(ComponentId),
+    Paste,
+    ApplyPreset(String, String),
+    SavePreset(ComponentId, String),
+    Remove(ComponentId),
+}
+
+/// The presets of the selected asset, to apply one or save its current value
+fn asset_presets_button(
+    world: &mut World,
+    asset_id: UntypedAssetId,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+) {
+    let Some(type_path) = type_registry
+        .get(asset_id.type_id())
+        .map(|registration| registration.type_info().type_path())
+    else {
+        return;
+    };
+    let Some(presets) = world.get_resource::<Presets>() else {
+        return;
+    };
+    match presets::presets_menu(ui, presets, type_path, true) {
+        Some(PresetAction::Apply(name)) => {
+            presets::apply_asset_preset(world, asset_id, type_path, &name);
+        }
+        Some(PresetAction::Save(name)) => presets::save_asset_preset(world, asset_id, &name),
+        None => {}
+    }
+}
+
+fn bookmark_button(world: &mut World, entity: Entity, ui: &mut egui::Ui) {
+    let (text, hover) = match bookmarks::is_bookmarked(world, entity) {
+        true => ("★", "Remove bookmark"),
+        false => ("☆", "Bookmark"),
+    };
+    ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+        if ui.button(text).on_hover_text(hover).clicked() {
+            bookmarks::toggle_bookmark(world, entity);
+        }
+    });
+}
 
 pub fn label_button(ui: &mut egui::Ui, text: &str, text_color: egui::Color32) -> bool {
     ui.add(egui::Button::new(egui::RichText::new(text).color(text_color)).frame(false))
@@ -1,17 +1,24 @@
 #![allow(clippy::type_complexity)]
 //! Default windows for the editor
-// pub mod add;
+pub mod add;
 pub mod assets;
+pub mod bookmarks;
 pub mod cameras;
+pub mod components;
 pub mod debug_settings;
 pub mod diagnostics;
+pub mod entity_field;
 pub mod gizmos;
 pub mod graph;
+pub mod handle_field;
 pub mod hierarchy;
 pub mod inspector;
 pub mod logging;
+pub mod multi_edit;
+pub mod presets;
 pub mod renderer;
 pub mod resources;
+pub mod watches;
 
 pub mod utils {
     pub mod log_plugin;
@@ -22,16 +29,47 @@ pub mod utils {
 pub mod metrics;
 // pub mod scenes;
 
+/// Where per-project editor data like bookmarks is saved.
+///
+/// Defaults to `.editor_pls` next to the `Cargo.toml` of the project when run through cargo, or in
+/// the working directory otherwise. Insert it before adding the editor windows to save the data
+/// somewhere else, like `EditorPlugin::with_data_dir` of `bevy_editor_pls` does.
+#[derive(bevy::prelude::Resource, Debug, Clone)]
+pub struct EditorDataDir(pub std::path::PathBuf);
+
+impl Default for EditorDataDir {
+    fn default() -> Self {
+        let project_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(std::path::PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        EditorDataDir(project_dir.join(".editor_pls"))
+    }
+}
+
+/// The [`EditorDataDir`], inserting the default one if the game didn't
+pub(crate) fn editor_data_dir(world: &mut bevy::prelude::World) -> std::path::PathBuf {
+    world
+        .get_resource_or_insert_with(EditorDataDir::default)
+        .0
+        .clone()
+}
+
 pub mod prelude {
+    pub use crate::add::AddWindow;
     pub use crate::assets::AssetsWindow;
+    pub use crate::bookmarks::BookmarksWindow;
     pub use crate::cameras::CameraWindow;
     pub use crate::debug_settings::DebugSettingsWindow;
     pub use crate::diagnostics::DiagnosticsWindow;
     pub use crate::gizmos::GizmosWindow;
+    pub use crate::hierarchy::query_view::QueryHierarchyWindow;
     pub use crate::hierarchy::HierarchyWindow;
     pub use crate::inspector::InspectorWindow;
+    pub use crate::presets::PresetsWindow;
     pub use crate::renderer::RendererWindow;
     pub use crate::resources::ResourcesWindow;
+    pub use crate::watches::WatchesWindow;
 
     #[cfg(feature = "bevy_metrics_dashboard")]
     pub use crate::metrics::MetricsWindow;
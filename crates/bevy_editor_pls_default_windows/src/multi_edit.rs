@@ -0,0 +1,384 @@
+//! Editing the numbers of multiple entities relative to each entity's own value, like moving all
+//! selected entities up by dragging `translation.y`, or doubling their `intensity` with `*2`.
+
+use bevy::ecs::component::ComponentId;
+use bevy::prelude::*;
+use bevy::reflect::{GetPath, ReflectRef};
+use bevy_inspector_egui::egui;
+
+use crate::components;
+
+/// Lists and arrays longer than this aren't expanded
+const MAX_LIST_LEN: usize = 16;
+
+/// An edit typed into the expression field of a number
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expression {
+    /// `+0.5`
+    Add(f64),
+    /// `-0.5`
+    Sub(f64),
+    /// `*2`
+    Mul(f64),
+    /// `/2`
+    Div(f64),
+    /// `=3` or `3`, sets all values to the same number
+    Set(f64),
+}
+
+impl Expression {
+    /// `None` for text that isn't an expression, and for dividing by zero
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (op, number) = match text.chars().next()? {
+            op @ ('+' | '-' | '*' | '/' | '=') => (Some(op), &text[1..]),
+            _ => (None, text),
+        };
+        let number: f64 = number.trim().parse().ok()?;
+        // `inf` and `NaN` parse as numbers too
+        if !number.is_finite() {
+            return None;
+        }
+        Some(match op {
+            Some('+') => Expression::Add(number),
+            Some('-') => Expression::Sub(number),
+            Some('*') => Expression::Mul(number),
+            Some('/') if number == 0.0 => return None,
+            Some('/') => Expression::Div(number),
+            _ => Expression::Set(number),
+        })
+    }
+
+    /// The new value, or `None` if it isn't a finite number
+    pub fn apply(self, value: f64) -> Option<f64> {
+        let result = match self {
+            Expression::Add(number) => value + number,
+            Expression::Sub(number) => value - number,
+            Expression::Mul(number) => value * number,
+            Expression::Div(number) => value / number,
+            Expression::Set(number) => number,
+        };
+        result.is_finite().then_some(result)
+    }
+}
+
+pub(crate) struct NumericField {
+    /// reflect path from the component, like `.translation.y`
+    pub path: String,
+    integer: bool,
+}
+
+pub(crate) fn numeric_fields(
+    value: &dyn PartialReflect,
+    path: &mut String,
+    fields: &mut Vec<NumericField>,
+) {
+    if let Some(integer) = is_integer(value) {
+        fields.push(NumericField {
+            path: path.clone(),
+            integer,
+        });
+        return;
+    }
+    // offsetting the components of a rotation makes no sense
+    if value.reflect_type_path() == "glam::Quat" {
+        return;
+    }
+
+    let len = path.len();
+    let mut field =
+        |path: &mut String, segment: std::fmt::Arguments, value: &dyn PartialReflect| {
+            use std::fmt::Write;
+            let _ = path.write_fmt(segment);
+            numeric_fields(value, path, fields);
+            path.truncate(len);
+        };
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for i in 0..value.field_len() {
+                if let (Some(name), Some(value)) = (value.name_at(i), value.field_at(i)) {
+                    field(path, format_args!(".{name}"), value);
+                }
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for (i, value) in value.iter_fields().enumerate() {
+                field(path, format_args!(".{i}"), value);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for (i, value) in value.iter_fields().enumerate() {
+                field(path, format_args!(".{i}"), value);
+            }
+        }
+        ReflectRef::Array(value) if value.len() <= MAX_LIST_LEN => {
+            for (i, value) in value.iter().enumerate() {
+                field(path, format_args!("[{i}]"), value);
+            }
+        }
+        ReflectRef::List(value) if value.len() <= MAX_LIST_LEN => {
+            for (i, value) in value.iter().enumerate() {
+                field(path, format_args!("[{i}]"), value);
+            }
+        }
+        // enum variants and map keys can differ between the entities
+        _ => {}
+    }
+}
+
+/// `Some(true)` for integers, `Some(false)` for floats, `None` for anything else
+fn is_integer(value: &dyn PartialReflect) -> Option<bool> {
+    get(value)?;
+    Some(!(value.represents::<f32>() || value.represents::<f64>()))
+}
+
+/// The value of any integer or float as `f64`
+pub(crate) fn get(value: &dyn PartialReflect) -> Option<f64> {
+    macro_rules! get {
+        ($($ty:ty),*) => {
+            $(if let Some(value) = value.try_downcast_ref::<$ty>() {
+                return Some(*value as f64);
+            })*
+        };
+    }
+    get!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    None
+}
+
+fn set(value: &mut dyn PartialReflect, number: f64) {
+    macro_rules! set {
+        ($number:expr, $($ty:ty),*) => {
+            $(if let Some(value) = value.try_downcast_mut::<$ty>() {
+                *value = $number as $ty;
+                return;
+            })*
+        };
+    }
+    set!(number, f32, f64);
+    // `as` saturates at the bounds of the integer type
+    set!(
+        number.round(),
+        i8,
+        i16,
+        i32,
+        i64,
+        isize,
+        u8,
+        u16,
+        u32,
+        u64,
+        usize
+    );
+}
+
+/// The values of `path` in each of `entities` that have it
+fn field_values(
+    world: &World,
+    entities: &[Entity],
+    reflect_component: &ReflectComponent,
+    path: &str,
+) -> Vec<f64> {
+    entities
+        .iter()
+        .filter_map(|&entity| {
+            let component = reflect_component.reflect(world.get_entity(entity).ok()?)?;
+            get(component.reflect_path(path).ok()?)
+        })
+        .collect()
+}
+
+fn apply(
+    world: &mut World,
+    entities: &[Entity],
+    reflect_component: &ReflectComponent,
+    path: &str,
+    expression: Expression,
+) {
+    for &entity in entities {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            continue;
+        };
+        let Some(mut component) = reflect_component.reflect_mut(&mut entity) else {
+            continue;
+        };
+        let Ok(value) = component.reflect_path_mut(path) else {
+            continue;
+        };
+        if let Some(number) = get(value).and_then(|number| expression.apply(number)) {
+            set(value, number);
+        }
+    }
+}
+
+/// The components shared by `entities`, with their numbers edited relative to each entity's value.
+///
+/// Dragging a number adds the dragged amount to every entity, typing `+1`, `-1`, `*2`, `/2`
+/// or `=1` and pressing enter applies that to every entity. Returns the header of each component.
+pub fn ui_for_entities_relative(
+    world: &mut World,
+    entities: &[Entity],
+    ui: &mut egui::Ui,
+) -> Vec<(ComponentId, egui::Response)> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let mut edits = Vec::new();
+    let mut headers = Vec::new();
+    for component_id in components::shared_components(world, entities) {
+        let Some(info) = world.components().get_info(component_id) else {
+            continue;
+        };
+        let name = pretty_type_name::pretty_type_name_str(info.name());
+        let Some(reflect_component) = info
+            .type_id()
+            .and_then(|type_id| type_registry.get_type_data::<ReflectComponent>(type_id))
+        else {
+            continue;
+        };
+        let Some(component) = reflect_component.reflect(world.entity(entities[0])) else {
+            continue;
+        };
+        let mut fields = Vec::new();
+        numeric_fields(
+            component.as_partial_reflect(),
+            &mut String::new(),
+            &mut fields,
+        );
+        if fields.is_empty() {
+            continue;
+        }
+
+        let header = egui::CollapsingHeader::new(name)
+            .id_salt(("relative", component_id))
+            .show(ui, |ui| {
+                egui::Grid::new(("relative_fields", component_id))
+                    .num_columns(4)
+                    .show(ui, |ui| {
+                        for field in fields {
+                            let values =
+                                field_values(world, entities, reflect_component, &field.path);
+                            if let Some(expression) = field_ui(ui, component_id, &field, &values) {
+                                edits.push((reflect_component, field.path, expression));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        headers.push((component_id, header.header_response));
+    }
+
+    for (reflect_component, path, expression) in edits {
+        apply(world, entities, reflect_component, &path, expression);
+    }
+    headers
+}
+
+fn field_ui(
+    ui: &mut egui::Ui,
+    component_id: ComponentId,
+    field: &NumericField,
+    values: &[f64],
+) -> Option<Expression> {
+    let mut edit = None;
+    ui.label(field.path.trim_start_matches('.'));
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+        ui.weak(format!("{min:.3}"));
+    } else {
+        ui.weak(format!("{min:.3} .. {max:.3}"));
+    }
+
+    // the amount dragged so far, reset when the drag ends
+    let delta_id = ui.id().with(("relative_delta", component_id, &field.path));
+    let mut delta = ui.data(|d| d.get_temp::<f64>(delta_id)).unwrap_or_default();
+    let previous = delta;
+    let response = ui
+        .add(
+            egui::DragValue::new(&mut delta)
+                .speed(if field.integer { 0.1 } else { 0.01 })
+                .prefix("Δ "),
+        )
+        .on_hover_text("Drag to add to every value");
+    let added = match field.integer {
+        true => delta.round() - previous.round(),
+        false => delta - previous,
+    };
+    if added != 0.0 {
+        edit = Some(Expression::Add(added));
+    }
+    if response.dragged() || response.has_focus() {
+        ui.data_mut(|d| d.insert_temp(delta_id, delta));
+    } else {
+        ui.data_mut(|d| d.remove::<f64>(delta_id));
+    }
+
+    let expression_id = ui
+        .id()
+        .with(("relative_expression", component_id, &field.path));
+    let mut text = ui
+        .data(|d| d.get_temp::<String>(expression_id))
+        .unwrap_or_default();
+    let invalid = !text.trim().is_empty() && Expression::parse(&text).is_none();
+    let mut text_edit = egui::TextEdit::singleline(&mut text)
+        .hint_text("+1, *2, =0")
+        .desired_width(60.0);
+    if invalid {
+        text_edit = text_edit.text_color(ui.visuals().error_fg_color);
+    }
+    let response = ui.add(text_edit);
+    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+        if let Some(expression) = Expression::parse(&text) {
+            edit = Some(expression);
+            text.clear();
+        }
+    }
+    ui.data_mut(|d| d.insert_temp(expression_id, text));
+
+    edit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_operators() {
+        assert_eq!(Expression::parse("+1"), Some(Expression::Add(1.0)));
+        assert_eq!(Expression::parse("-0.5"), Some(Expression::Sub(0.5)));
+        assert_eq!(Expression::parse("*2"), Some(Expression::Mul(2.0)));
+        assert_eq!(Expression::parse("/4"), Some(Expression::Div(4.0)));
+        assert_eq!(Expression::parse("=0"), Some(Expression::Set(0.0)));
+        assert_eq!(Expression::parse(" * 2 "), Some(Expression::Mul(2.0)));
+    }
+
+    #[test]
+    fn parse_bare_number_sets() {
+        assert_eq!(Expression::parse("3"), Some(Expression::Set(3.0)));
+        assert_eq!(Expression::parse("1.5e2"), Some(Expression::Set(150.0)));
+    }
+
+    #[test]
+    fn parse_rejects_bad_input() {
+        for text in ["", " ", "+", "abc", "*x", "2*", "inf", "/0", "/-0.0"] {
+            assert_eq!(Expression::parse(text), None, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn apply() {
+        assert_eq!(Expression::Add(1.0).apply(2.0), Some(3.0));
+        assert_eq!(Expression::Sub(1.0).apply(2.0), Some(1.0));
+        assert_eq!(Expression::Mul(2.0).apply(-3.0), Some(-6.0));
+        assert_eq!(Expression::Div(4.0).apply(2.0), Some(0.5));
+        assert_eq!(Expression::Set(7.0).apply(2.0), Some(7.0));
+    }
+
+    #[test]
+    fn apply_rejects_non_finite_results() {
+        assert_eq!(Expression::Mul(2.0).apply(f64::MAX), None);
+        assert_eq!(Expression::Div(1e-300).apply(1e300), None);
+        assert_eq!(Expression::Add(1.0).apply(f64::NAN), None);
+    }
+}
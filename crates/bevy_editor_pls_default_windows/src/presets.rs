@@ -0,0 +1,481 @@
+//! Named values of components and assets, like a "Warm key light" for `PointLight` or a "Soft
+//! material" for `StandardMaterial`, applied to selected entities or assets.
+//!
+//! Each preset is saved as RON to `presets/<type path>/<name>.ron` in the
+//! [`EditorDataDir`](crate::EditorDataDir).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use bevy::asset::{ReflectAsset, UntypedAssetId};
+use bevy::ecs::component::ComponentId;
+use bevy::prelude::*;
+use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy::reflect::{TypeRegistration, TypeRegistry};
+use bevy::scene::ron;
+use bevy_editor_pls_core::editor_window::{EditorWindow, EditorWindowContext};
+use bevy_editor_pls_core::AddEditorWindow;
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::reflect_inspector;
+
+use crate::hierarchy::{is_locked, HierarchyState};
+use crate::inspector::{InspectorSelection, InspectorState};
+
+/// Where presets are saved, relative to [`crate::editor_data_dir`]
+const PRESETS_DIR: &str = "presets";
+
+pub struct Preset {
+    pub name: String,
+    pub value: Box<dyn PartialReflect>,
+}
+
+/// Presets of each component and asset type, by type path
+#[derive(Resource)]
+pub struct Presets {
+    dir: PathBuf,
+    pub by_type: BTreeMap<String, Vec<Preset>>,
+}
+
+impl Presets {
+    /// No presets yet, saved to `dir`
+    pub fn new(dir: PathBuf) -> Self {
+        Presets {
+            dir,
+            by_type: BTreeMap::new(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn type_dir(&self, type_path: &str) -> PathBuf {
+        self.dir.join(file_name(type_path))
+    }
+
+    /// Reads the presets of every registered component and asset type
+    pub fn load(&mut self, type_registry: &TypeRegistry) {
+        self.by_type.clear();
+        for registration in type_registry.iter() {
+            if registration.data::<ReflectComponent>().is_none()
+                && registration.data::<ReflectAsset>().is_none()
+            {
+                continue;
+            }
+            let type_path = registration.type_info().type_path();
+            let dir = self.type_dir(type_path);
+            if !dir.is_dir() {
+                continue;
+            }
+
+            let presets = load_type_dir(&dir, registration, type_registry);
+            if !presets.is_empty() {
+                self.by_type.insert(type_path.to_owned(), presets);
+            }
+        }
+    }
+
+    pub fn get(&self, type_path: &str) -> &[Preset] {
+        self.by_type.get(type_path).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn find(&self, type_path: &str, name: &str) -> Option<&Preset> {
+        self.get(type_path)
+            .iter()
+            .find(|preset| preset.name == name)
+    }
+
+    /// Saves `value` as a preset of its type, replacing a preset of the same name
+    pub fn save(
+        &mut self,
+        name: &str,
+        value: Box<dyn PartialReflect>,
+        type_registry: &TypeRegistry,
+    ) {
+        let Some(type_path) = value
+            .get_represented_type_info()
+            .map(|info| info.type_path().to_owned())
+        else {
+            return;
+        };
+        let name = file_name(name);
+
+        let result = ron::ser::to_string_pretty(
+            &TypedReflectSerializer::new(value.as_ref(), type_registry),
+            default(),
+        )
+        .map_err(|e| e.to_string())
+        .and_then(|text| {
+            let dir = self.type_dir(&type_path);
+            std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            std::fs::write(dir.join(format!("{name}.ron")), text).map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            warn!("Failed to save preset {name}: {}", e);
+            return;
+        }
+
+        let presets = self.by_type.entry(type_path).or_default();
+        presets.retain(|preset| preset.name != name);
+        presets.push(Preset { name, value });
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn remove(&mut self, type_path: &str, name: &str) {
+        let Some(presets) = self.by_type.get_mut(type_path) else {
+            return;
+        };
+        presets.retain(|preset| preset.name != name);
+        if presets.is_empty() {
+            self.by_type.remove(type_path);
+        }
+
+        let file = self.type_dir(type_path).join(format!("{name}.ron"));
+        if let Err(e) = std::fs::remove_file(file) {
+            warn!("Failed to remove preset {name}: {}", e);
+        }
+    }
+}
+
+fn load_type_dir(
+    dir: &Path,
+    registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+) -> Vec<Preset> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read presets in {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut presets: Vec<Preset> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| {
+                    ron::Options::default()
+                        .from_str_seed(
+                            &text,
+                            TypedReflectDeserializer::new(registration, type_registry),
+                        )
+                        .map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(value) => Some(Preset { name, value }),
+                Err(e) => {
+                    warn!("Failed to load preset {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    presets
+}
+
+/// Replaces the characters that aren't allowed in file names on some platforms
+fn file_name(name: &str) -> String {
+    name.replace("::", ".")
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Saves the value of a component of `entity` as a preset named `name`
+pub fn save_preset(world: &mut World, entity: Entity, component_id: ComponentId, name: &str) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let Some(type_id) = world
+        .components()
+        .get_info(component_id)
+        .and_then(|info| info.type_id())
+    else {
+        return;
+    };
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) else {
+        return;
+    };
+    let Some(value) = world
+        .get_entity(entity)
+        .ok()
+        .and_then(|entity| reflect_component.reflect(entity))
+        .map(|value| value.clone_value())
+    else {
+        return;
+    };
+
+    world
+        .resource_mut::<Presets>()
+        .save(name, value, &type_registry);
+}
+
+/// Applies a preset to each of `entities`, inserting the component where it's missing
+pub fn apply_preset(world: &mut World, entities: &[Entity], type_path: &str, name: &str) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some(reflect_component) = type_registry
+        .get_with_type_path(type_path)
+        .and_then(|registration| registration.data::<ReflectComponent>())
+    else {
+        return;
+    };
+
+    world.resource_scope(|world, presets: Mut<Presets>| {
+        let Some(preset) = presets.find(type_path, name) else {
+            return;
+        };
+        for &entity in entities {
+            if is_locked(world, entity) {
+                continue;
+            }
+            if let Ok(mut entity) = world.get_entity_mut(entity) {
+                reflect_component.apply_or_insert(
+                    &mut entity,
+                    preset.value.as_ref(),
+                    &type_registry,
+                );
+            }
+        }
+    });
+}
+
+/// Saves the value of the asset `asset_id` as a preset named `name`
+pub fn save_asset_preset(world: &mut World, asset_id: UntypedAssetId, name: &str) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let Some(reflect_asset) = type_registry.get_type_data::<ReflectAsset>(asset_id.type_id())
+    else {
+        return;
+    };
+    let Some(value) = reflect_asset
+        .get(world, UntypedHandle::Weak(asset_id))
+        .map(|value| value.clone_value())
+    else {
+        return;
+    };
+
+    world
+        .resource_mut::<Presets>()
+        .save(name, value, &type_registry);
+}
+
+/// Applies a preset to the asset `asset_id`, if the preset is of its type
+pub fn apply_asset_preset(
+    world: &mut World,
+    asset_id: UntypedAssetId,
+    type_path: &str,
+    name: &str,
+) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some(reflect_asset) = type_registry
+        .get_with_type_path(type_path)
+        .and_then(|registration| registration.data::<ReflectAsset>())
+        .filter(|reflect_asset| reflect_asset.asset_type_id() == asset_id.type_id())
+    else {
+        return;
+    };
+
+    world.resource_scope(|world, presets: Mut<Presets>| {
+        let Some(preset) = presets.find(type_path, name) else {
+            return;
+        };
+        if let Some(asset) = reflect_asset.get_mut(world, UntypedHandle::Weak(asset_id)) {
+            asset.apply(preset.value.as_ref());
+        }
+    });
+}
+
+pub enum PresetAction {
+    Apply(String),
+    Save(String),
+}
+
+/// Presets of `type_path` to apply, and a name field to save the current value when `can_save`
+pub fn presets_menu(
+    ui: &mut egui::Ui,
+    presets: &Presets,
+    type_path: &str,
+    can_save: bool,
+) -> Option<PresetAction> {
+    let mut action = None;
+    ui.menu_button("Presets", |ui| {
+        let presets = presets.get(type_path);
+        if presets.is_empty() {
+            ui.weak("No presets");
+        }
+        for preset in presets {
+            if ui.button(&preset.name).clicked() {
+                action = Some(PresetAction::Apply(preset.name.clone()));
+                ui.close_menu();
+            }
+        }
+
+        if !can_save {
+            return;
+        }
+        ui.separator();
+        let id = ui.id().with(("preset_name", type_path));
+        let mut name = ui.data(|d| d.get_temp::<String>(id)).unwrap_or_default();
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut name)
+                    .hint_text("Preset name")
+                    .desired_width(120.0),
+            );
+            let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let save = ui.add_enabled(!name.trim().is_empty(), egui::Button::new("Save"));
+            if (save.clicked() || enter) && !name.trim().is_empty() {
+                action = Some(PresetAction::Save(name.trim().to_owned()));
+                name.clear();
+                ui.close_menu();
+            }
+        });
+        ui.data_mut(|d| d.insert_temp(id, name));
+    });
+    action
+}
+
+fn load_presets(type_registry: Res<AppTypeRegistry>, mut presets: ResMut<Presets>) {
+    presets.load(&type_registry.read());
+}
+
+/// What the `Apply` button of a preset in the [`PresetsWindow`] applies to
+#[derive(Clone, Copy)]
+enum PresetTarget {
+    Entities,
+    Asset(UntypedAssetId),
+}
+
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct PresetsWindow;
+
+impl EditorWindow for PresetsWindow {
+    fn ui(&self, world: &mut World, cx: EditorWindowContext, ui: &mut egui::Ui) {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let selected: Vec<Entity> = cx
+            .get::<HierarchyState>(world)
+            .map(|state| state.selected.iter().collect())
+            .unwrap_or_default();
+        let selected_asset =
+            cx.get::<InspectorState>(world)
+                .and_then(|state| match state.selected {
+                    InspectorSelection::Asset(_, _, asset_id) => Some(asset_id),
+                    _ => None,
+                });
+
+        let mut apply = None;
+        let mut remove = None;
+        {
+            let presets = world.resource::<Presets>();
+            if presets.by_type.is_empty() {
+                ui.label("Save presets from the menu of a component or asset in the inspector");
+            }
+
+            for (type_path, type_presets) in &presets.by_type {
+                let name = pretty_type_name::pretty_type_name_str(type_path);
+                // asset presets apply to the selected asset of their type, the others to entities
+                let asset_type_id = type_registry
+                    .get_with_type_path(type_path)
+                    .and_then(|registration| registration.data::<ReflectAsset>())
+                    .map(ReflectAsset::asset_type_id);
+                let (target, hover, disabled_hover) = match asset_type_id {
+                    Some(type_id) => (
+                        selected_asset
+                            .filter(|asset_id| asset_id.type_id() == type_id)
+                            .map(PresetTarget::Asset),
+                        "Apply to the selected asset",
+                        "Select an asset of this type to apply to",
+                    ),
+                    None => (
+                        (!selected.is_empty()).then_some(PresetTarget::Entities),
+                        "Apply to the selected entities",
+                        "Select entities to apply to",
+                    ),
+                };
+                egui::CollapsingHeader::new(name)
+                    .id_salt(type_path)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for preset in type_presets {
+                            let id = ui.make_persistent_id((type_path, &preset.name));
+                            egui::collapsing_header::CollapsingState::load_with_default_open(
+                                ui.ctx(),
+                                id,
+                                false,
+                            )
+                            .show_header(ui, |ui| {
+                                ui.label(&preset.name);
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button("✖").on_hover_text("Remove").clicked()
+                                        {
+                                            remove = Some((type_path.clone(), preset.name.clone()));
+                                        }
+                                        let apply_button = ui
+                                            .add_enabled(
+                                                target.is_some(),
+                                                egui::Button::new("Apply").small(),
+                                            )
+                                            .on_hover_text(hover)
+                                            .on_disabled_hover_text(disabled_hover);
+                                        if let (true, Some(target)) =
+                                            (apply_button.clicked(), target)
+                                        {
+                                            apply = Some((
+                                                target,
+                                                type_path.clone(),
+                                                preset.name.clone(),
+                                            ));
+                                        }
+                                    },
+                                );
+                            })
+                            .body(|ui| {
+                                reflect_inspector::ui_for_value_readonly(
+                                    preset.value.as_ref(),
+                                    ui,
+                                    &type_registry,
+                                );
+                            });
+                        }
+                    });
+            }
+        }
+
+        match apply {
+            Some((PresetTarget::Entities, type_path, name)) => {
+                apply_preset(world, &selected, &type_path, &name);
+            }
+            Some((PresetTarget::Asset(asset_id), type_path, name)) => {
+                apply_asset_preset(world, asset_id, &type_path, &name);
+            }
+            None => {}
+        }
+        if let Some((type_path, name)) = remove {
+            world.resource_mut::<Presets>().remove(&type_path, &name);
+        }
+    }
+}
+
+impl Plugin for PresetsWindow {
+    fn build(&self, app: &mut App) {
+        app.add_editor_window::<Self>();
+        let dir = crate::editor_data_dir(app.world_mut()).join(PRESETS_DIR);
+        app.insert_resource(Presets::new(dir))
+            .add_systems(Startup, load_presets);
+    }
+}
@@ -0,0 +1,489 @@
+//! Numbers of components watched over time, like the `translation.y` of a bouncing ball or the
+//! `current` of a `Health`.
+//!
+//! A watch samples its field every frame and is identified by its entity, component and reflect
+//! path, so it keeps sampling while other entities are selected.
+//!
+//! Numbers are watched by right clicking them in the inspector. The ui is registered in the
+//! [`AppTypeRegistry`] around the default ui of numbers and vectors.
+
+use std::any::{Any, TypeId};
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use bevy::ecs::component::ComponentId;
+use bevy::math::{DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec3A};
+use bevy::prelude::*;
+use bevy::reflect::{GetPath, TypeRegistry};
+use bevy_editor_pls_core::editor_window::{EditorWindow, EditorWindowContext};
+use bevy_editor_pls_core::AddEditorWindow;
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::inspector_egui_impls::InspectorEguiImpl;
+use bevy_inspector_egui::reflect_inspector::{InspectorUi, ProjectorReflect};
+
+use crate::multi_edit;
+
+/// How many frames of values are kept for each watch
+const MAX_SAMPLES: usize = 600;
+
+pub struct Watch {
+    pub entity: Entity,
+    pub component_type_id: TypeId,
+    /// reflect path from the component, like `.translation.y`
+    pub path: String,
+    /// like `Transform.translation.y`
+    pub label: String,
+    /// seconds since startup and the value at that time
+    pub samples: VecDeque<[f64; 2]>,
+}
+
+impl Watch {
+    pub fn current(&self) -> Option<f64> {
+        self.samples.back().map(|[_, value]| *value)
+    }
+
+    pub fn min_max(&self) -> Option<(f64, f64)> {
+        self.samples.iter().fold(None, |min_max, &[_, value]| {
+            Some(match min_max {
+                Some((min, max)) => (f64::min(min, value), f64::max(max, value)),
+                None => (value, value),
+            })
+        })
+    }
+
+    fn is(&self, entity: Entity, component_type_id: TypeId, path: &str) -> bool {
+        self.entity == entity && self.component_type_id == component_type_id && self.path == path
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Watches {
+    pub watches: Vec<Watch>,
+    /// stops sampling, to look at the plots
+    pub paused: bool,
+}
+
+impl Watches {
+    pub fn contains(&self, entity: Entity, component_type_id: TypeId, path: &str) -> bool {
+        self.watches
+            .iter()
+            .any(|watch| watch.is(entity, component_type_id, path))
+    }
+
+    pub fn add(&mut self, entity: Entity, component_type_id: TypeId, path: &str, label: String) {
+        if self.contains(entity, component_type_id, path) {
+            return;
+        }
+        self.watches.push(Watch {
+            entity,
+            component_type_id,
+            path: path.to_owned(),
+            label,
+            samples: VecDeque::with_capacity(MAX_SAMPLES),
+        });
+    }
+
+    pub fn remove(&mut self, entity: Entity, component_type_id: TypeId, path: &str) {
+        self.watches
+            .retain(|watch| !watch.is(entity, component_type_id, path));
+    }
+}
+
+/// Watches `path` of a component on each of `entities`, or stops watching it when all of them are
+pub fn toggle_watch(world: &mut World, entities: &[Entity], component_id: ComponentId, path: &str) {
+    let Some(info) = world.components().get_info(component_id) else {
+        return;
+    };
+    let Some(type_id) = info.type_id() else {
+        return;
+    };
+    let label = format!(
+        "{}{path}",
+        pretty_type_name::pretty_type_name_str(info.name())
+    );
+
+    let mut watches = world.resource_mut::<Watches>();
+    let watched = entities
+        .iter()
+        .all(|&entity| watches.contains(entity, type_id, path));
+    for &entity in entities {
+        match watched {
+            true => watches.remove(entity, type_id, path),
+            false => watches.add(entity, type_id, path, label.clone()),
+        }
+    }
+}
+
+/// The numbers of the component the inspector is drawing, for a right click on one of its fields
+#[derive(Clone)]
+struct DrawnComponent {
+    entities: Vec<Entity>,
+    component_id: ComponentId,
+    component_type_id: TypeId,
+    /// address of each number in the component of the first entity, and its reflect path
+    fields: Vec<(usize, String)>,
+}
+
+fn drawn_component_id() -> egui::Id {
+    egui::Id::new("watches_drawn_component")
+}
+
+fn address<T: ?Sized>(value: &T) -> usize {
+    value as *const T as *const () as usize
+}
+
+/// Remembers the numbers of the component the inspector is about to draw, so the fields know what
+/// to watch when right clicked. `value` is the component of the first of `entities`.
+pub(crate) fn begin_component(
+    ui: &egui::Ui,
+    entities: &[Entity],
+    component_id: ComponentId,
+    component_type_id: TypeId,
+    value: &dyn Reflect,
+) {
+    if !ui.input(|i| i.pointer.secondary_clicked()) {
+        return;
+    }
+    let mut fields = Vec::new();
+    multi_edit::numeric_fields(value.as_partial_reflect(), &mut String::new(), &mut fields);
+    let fields = fields
+        .into_iter()
+        .filter_map(|field| {
+            let number = value.reflect_path(field.path.as_str()).ok()?;
+            Some((address(number), field.path))
+        })
+        .collect();
+    let drawn = DrawnComponent {
+        entities: entities.to_vec(),
+        component_id,
+        component_type_id,
+        fields,
+    };
+    ui.data_mut(|d| d.insert_temp(drawn_component_id(), drawn));
+}
+
+/// Called once the component of [`begin_component`] is drawn
+pub(crate) fn end_component(ui: &egui::Ui) {
+    ui.data_mut(|d| d.remove::<DrawnComponent>(drawn_component_id()));
+}
+
+/// The ui numbers and vectors had before they could be watched
+#[derive(Clone)]
+struct PreviousNumberUi(InspectorEguiImpl);
+
+/// Wraps the ui of numbers and vectors to watch them from a right click. Runs after the default ui
+/// is registered.
+fn register_watch_ui(world: &mut World) {
+    fn wrap<T: 'static>(type_registry: &mut TypeRegistry) {
+        let Some(registration) = type_registry.get_mut(TypeId::of::<T>()) else {
+            return;
+        };
+        if registration.data::<PreviousNumberUi>().is_some() {
+            return;
+        }
+        let Some(previous) = registration.data::<InspectorEguiImpl>().cloned() else {
+            return;
+        };
+        registration.insert(PreviousNumberUi(previous));
+        registration.insert(InspectorEguiImpl::new(
+            number_ui::<T>,
+            number_ui_readonly::<T>,
+            number_ui_many::<T>,
+        ));
+    }
+    macro_rules! wrap {
+        ($type_registry:expr, $($ty:ty),*) => {
+            $(wrap::<$ty>($type_registry);)*
+        };
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let mut type_registry = type_registry.write();
+    wrap!(
+        &mut type_registry,
+        f32,
+        f64,
+        i8,
+        i16,
+        i32,
+        i64,
+        isize,
+        u8,
+        u16,
+        u32,
+        u64,
+        usize
+    );
+    wrap!(
+        &mut type_registry,
+        Vec2,
+        Vec3,
+        Vec3A,
+        Vec4,
+        DVec2,
+        DVec3,
+        DVec4,
+        IVec2,
+        IVec3,
+        IVec4,
+        UVec2,
+        UVec3,
+        UVec4
+    );
+}
+
+fn number_ui<T: 'static>(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    options: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) -> bool {
+    let type_registry = env.type_registry;
+    let Some(PreviousNumberUi(previous)) = type_registry.get_type_data(TypeId::of::<T>()) else {
+        return false;
+    };
+    let start = address(value);
+    let scope = ui.scope(|ui| previous.execute(value, ui, options, id, env.reborrow()));
+    watch_popup::<T>(ui, id, &scope.response, start, &mut env);
+    scope.inner
+}
+
+fn number_ui_readonly<T: 'static>(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    options: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) {
+    let type_registry = env.type_registry;
+    let Some(PreviousNumberUi(previous)) = type_registry.get_type_data(TypeId::of::<T>()) else {
+        return;
+    };
+    let scope = ui.scope(|ui| previous.execute_readonly(value, ui, options, id, env.reborrow()));
+    watch_popup::<T>(ui, id, &scope.response, address(value), &mut env);
+}
+
+fn number_ui_many<T: 'static>(
+    ui: &mut egui::Ui,
+    options: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+) -> bool {
+    let type_registry = env.type_registry;
+    let Some(PreviousNumberUi(previous)) = type_registry.get_type_data(TypeId::of::<T>()) else {
+        return false;
+    };
+    // the drawn component is the one of the first entity
+    let start = values.first_mut().map(|value| address(projector(*value)));
+    let scope =
+        ui.scope(|ui| previous.execute_many(ui, options, id, env.reborrow(), values, projector));
+    if let Some(start) = start {
+        watch_popup::<T>(ui, id, &scope.response, start, &mut env);
+    }
+    scope.inner
+}
+
+/// Opens a popup to watch the numbers of the field on a right click, all of them for vectors.
+/// `start` is the address of the field, a `T`.
+fn watch_popup<T>(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    response: &egui::Response,
+    start: usize,
+    env: &mut InspectorUi<'_, '_>,
+) {
+    let popup_id = id.with("watch");
+    let range: Range<usize> = start..start + std::mem::size_of::<T>();
+    // the field can be disabled while its entity is locked, so its response doesn't see clicks
+    if ui.rect_contains_pointer(response.rect) && ui.input(|i| i.pointer.secondary_clicked()) {
+        if let Some(mut drawn) = ui.data(|d| d.get_temp::<DrawnComponent>(drawn_component_id())) {
+            drawn.fields.retain(|(address, _)| range.contains(address));
+            if !drawn.fields.is_empty() {
+                ui.data_mut(|d| d.insert_temp(popup_id, drawn));
+                ui.memory_mut(|m| m.open_popup(popup_id));
+            }
+        }
+    }
+
+    if !ui.memory(|m| m.is_popup_open(popup_id)) {
+        return;
+    }
+    let Some(drawn) = ui.data(|d| d.get_temp::<DrawnComponent>(popup_id)) else {
+        return;
+    };
+    let Some(watches) = env
+        .context
+        .world
+        .as_mut()
+        .and_then(|world| world.get_resource_mut::<Watches>().ok())
+    else {
+        return;
+    };
+
+    let mut toggled = None;
+    egui::popup_below_widget(
+        ui,
+        popup_id,
+        response,
+        egui::PopupCloseBehavior::CloseOnClickOutside,
+        |ui| {
+            for (_, path) in &drawn.fields {
+                let mut watched = drawn
+                    .entities
+                    .iter()
+                    .all(|&entity| watches.contains(entity, drawn.component_type_id, path));
+                let label = format!("Watch {}", path.trim_start_matches('.'));
+                if ui.checkbox(&mut watched, label).clicked() {
+                    toggled = Some(path.clone());
+                }
+            }
+        },
+    );
+
+    let Some(path) = toggled else {
+        return;
+    };
+    let DrawnComponent {
+        entities,
+        component_id,
+        ..
+    } = drawn;
+    let toggle = move |world: &mut World| toggle_watch(world, &entities, component_id, &path);
+    match env.context.queue.as_deref_mut() {
+        Some(queue) => queue.push(toggle),
+        None => warn!("Can't watch numbers from here"),
+    }
+}
+
+fn sample_watches(world: &mut World) {
+    if world.resource::<Watches>().paused {
+        return;
+    }
+    let Some(now) = world
+        .get_resource::<Time<Real>>()
+        .map(|time| time.elapsed_secs_f64())
+    else {
+        return;
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    world.resource_scope(|world, mut watches: Mut<Watches>| {
+        for watch in &mut watches.watches {
+            let value = type_registry
+                .get_type_data::<ReflectComponent>(watch.component_type_id)
+                .zip(world.get_entity(watch.entity).ok())
+                .and_then(|(reflect_component, entity)| reflect_component.reflect(entity))
+                .and_then(|component| component.reflect_path(watch.path.as_str()).ok())
+                .and_then(multi_edit::get);
+            let Some(value) = value else {
+                continue;
+            };
+            if watch.samples.len() == MAX_SAMPLES {
+                watch.samples.pop_front();
+            }
+            watch.samples.push_back([now, value]);
+        }
+    });
+}
+
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct WatchesWindow;
+
+impl EditorWindow for WatchesWindow {
+    fn ui(&self, world: &mut World, _cx: EditorWindowContext, ui: &mut egui::Ui) {
+        let names: Vec<String> = world
+            .resource::<Watches>()
+            .watches
+            .iter()
+            .map(|watch| match world.get_entity(watch.entity) {
+                Ok(entity) => match entity.get::<Name>() {
+                    Some(name) => format!("{} ({})", name.as_str(), watch.entity),
+                    None => watch.entity.to_string(),
+                },
+                Err(_) => format!("{} (despawned)", watch.entity),
+            })
+            .collect();
+
+        let mut watches = world.resource_mut::<Watches>();
+        if watches.watches.is_empty() {
+            ui.label("Watch numbers by right clicking them in the inspector");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut watches.paused, "Pause");
+            if ui
+                .button("Clear")
+                .on_hover_text("Clear the samples")
+                .clicked()
+            {
+                for watch in &mut watches.watches {
+                    watch.samples.clear();
+                }
+            }
+        });
+        ui.separator();
+
+        let mut remove = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, (watch, name)) in watches.watches.iter().zip(names).enumerate() {
+                let id = ui.make_persistent_id(("watch", watch.entity, &watch.label));
+                egui::collapsing_header::CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    id,
+                    true,
+                )
+                .show_header(ui, |ui| {
+                    ui.label(format!("{name} {}", watch.label));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text("Stop watching")
+                            .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                    });
+                })
+                .body(|ui| watch_ui(ui, id, watch));
+            }
+        });
+
+        if let Some(i) = remove {
+            watches.watches.remove(i);
+        }
+    }
+}
+
+fn watch_ui(ui: &mut egui::Ui, id: egui::Id, watch: &Watch) {
+    let (Some(current), Some((min, max))) = (watch.current(), watch.min_max()) else {
+        ui.weak("No samples");
+        return;
+    };
+    ui.label(format!(
+        "current {current:.3}   min {min:.3}   max {max:.3}"
+    ));
+
+    let points: Vec<[f64; 2]> = watch.samples.iter().copied().collect();
+    egui_plot::Plot::new(id.with("plot"))
+        .height(120.0)
+        .allow_scroll(false)
+        .show(ui, |plot_ui| plot_ui.line(egui_plot::Line::new(points)));
+}
+
+impl Plugin for WatchesWindow {
+    fn build(&self, app: &mut App) {
+        app.add_editor_window::<Self>();
+        app.init_resource::<Watches>()
+            .add_systems(Last, sample_watches);
+    }
+
+    fn finish(&self, app: &mut App) {
+        // after `DefaultInspectorConfigPlugin` registered the ui of numbers
+        register_watch_ui(app.world_mut());
+    }
+}